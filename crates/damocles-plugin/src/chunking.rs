@@ -0,0 +1,199 @@
+//! Content-defined chunking for [FileStorage] uploads.
+//!
+//! Large proof-input blobs sent to the fileserver tend to overlap heavily
+//! across proving jobs (re-proving adjacent sectors, resubmitted windows,
+//! ...). Instead of uploading each blob in full, we split it into
+//! variable-length chunks using a rolling hash, address each chunk by its
+//! blake3 digest, and only upload chunks the server doesn't already have.
+//! The artifact actually referenced by a [ProgramData::Input] becomes a
+//! small manifest listing the ordered chunk digests.
+
+use std::collections::HashSet;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::filestorage::FileStorage;
+
+/// Minimum chunk size, in bytes.
+const MIN_CHUNK_SIZE: usize = 256 * 1024;
+/// Target average chunk size, in bytes.
+const AVG_CHUNK_SIZE: usize = 1024 * 1024;
+/// Maximum chunk size, in bytes.
+const MAX_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Directory chunks are stored under, addressed by their blake3 digest.
+const CHUNK_DIR: &str = "chunks";
+
+/// Number of low bits of the rolling hash that must be zero to emit a chunk
+/// boundary; tuned so the expected chunk size is [AVG_CHUNK_SIZE].
+const BOUNDARY_BITS: u32 = (AVG_CHUNK_SIZE as u32).trailing_zeros();
+const BOUNDARY_MASK: u32 = (1 << BOUNDARY_BITS) - 1;
+/// Window size, in bytes, the rolling hash is computed over.
+const WINDOW_SIZE: usize = 64;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    /// blake3 digests of the chunks, in order.
+    pub chunks: Vec<String>,
+}
+
+impl FileStorage {
+    /// Splits `data` into content-defined chunks, uploads any chunk the server
+    /// doesn't already have under `chunks/<digest>`, and writes a manifest
+    /// listing all chunk digests in order under `name`.
+    pub fn write_chunked(&self, name: &str, data: &[u8]) -> Result<ChunkManifest> {
+        let known = self.known_chunks().context("list known chunks")?;
+
+        let mut digests = Vec::new();
+        for chunk in split_chunks(data) {
+            let digest = blake3::hash(chunk).to_hex().to_string();
+            if !known.contains(&digest) {
+                self.write_sealed(&format!("{CHUNK_DIR}/{digest}"), chunk)
+                    .with_context(|| format!("write chunk {digest}"))?;
+            }
+            digests.push(digest);
+        }
+
+        let manifest = ChunkManifest { chunks: digests };
+        let manifest_bytes =
+            serde_json::to_vec(&manifest).context("encode chunk manifest")?;
+        self.write_sealed(name, &manifest_bytes)
+            .with_context(|| format!("write chunk manifest {name}"))?;
+        Ok(manifest)
+    }
+
+    /// Reads a manifest written by [FileStorage::write_chunked] and reassembles
+    /// the original blob by fetching each referenced chunk.
+    pub fn read_chunked(&self, name: &str) -> Result<Vec<u8>> {
+        let manifest_bytes = self
+            .read_sealed(name)
+            .with_context(|| format!("read chunk manifest {name}"))?;
+        let manifest: ChunkManifest =
+            serde_json::from_slice(&manifest_bytes).context("decode chunk manifest")?;
+
+        let mut out = Vec::new();
+        for digest in &manifest.chunks {
+            let chunk = self
+                .read_sealed(&format!("{CHUNK_DIR}/{digest}"))
+                .with_context(|| format!("read chunk {digest}"))?;
+            out.extend_from_slice(&chunk);
+        }
+        Ok(out)
+    }
+
+    /// Digests of chunks already present under [CHUNK_DIR] on the server.
+    fn known_chunks(&self) -> Result<HashSet<String>> {
+        match self.list(&format!("{CHUNK_DIR}/")) {
+            Ok(entries) => Ok(entries.into_iter().map(|entry| entry.name().to_string()).collect()),
+            // A fresh fileserver may not have a chunks directory yet.
+            Err(_) => Ok(HashSet::new()),
+        }
+    }
+}
+
+/// Splits `data` into content-defined chunks using a buzhash rolling hash over
+/// a sliding window, emitting a boundary whenever the low [BOUNDARY_BITS] bits
+/// of the hash are zero, subject to [MIN_CHUNK_SIZE]/[MAX_CHUNK_SIZE].
+fn split_chunks(data: &[u8]) -> Vec<&[u8]> {
+    if data.len() <= MIN_CHUNK_SIZE {
+        return vec![data];
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut hash: u32 = 0;
+
+    let mut i = 0;
+    while i < data.len() {
+        let rel = i - start;
+        let len = rel + 1;
+        hash = hash.rotate_left(1) ^ buzhash_table(data[i]);
+        if rel >= WINDOW_SIZE {
+            // Evict the byte that just fell outside the trailing edge of the window. Its
+            // contribution to `hash` has been left-rotated once per step since it was
+            // inserted, i.e. by `WINDOW_SIZE` bits by now, so XOR it back out rotated the
+            // same amount to cancel it exactly.
+            let evicted = data[start + rel - WINDOW_SIZE];
+            hash ^= buzhash_table(evicted).rotate_left((WINDOW_SIZE % 32) as u32);
+        }
+
+        let at_boundary = len >= WINDOW_SIZE && (hash & BOUNDARY_MASK) == 0;
+        if (len >= MIN_CHUNK_SIZE && at_boundary) || len >= MAX_CHUNK_SIZE {
+            chunks.push(&data[start..=i]);
+            start = i + 1;
+            hash = 0;
+        }
+        i += 1;
+    }
+
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+
+    chunks
+}
+
+/// Pseudo-random per-byte table entry for the buzhash rolling hash, derived
+/// from blake3 so we don't need to ship a 256-entry constant table.
+fn buzhash_table(byte: u8) -> u32 {
+    let digest = blake3::hash(&[byte]);
+    let bytes = digest.as_bytes();
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::*;
+    use crate::filestorage::init_operator;
+    use crate::filestorage::StorageParams;
+
+    fn memory_fs() -> FileStorage {
+        let op = init_operator(&StorageParams::Memory).unwrap();
+        FileStorage::new(op.blocking(), Url::parse("http://localhost/").unwrap(), None)
+    }
+
+    #[test]
+    fn test_split_chunks_reassembles_to_original() {
+        let data: Vec<u8> = (0..4 * AVG_CHUNK_SIZE as u32).map(|i| i as u8).collect();
+        let chunks = split_chunks(&data);
+        assert!(chunks.len() > 1, "input should be split into multiple chunks");
+        assert_eq!(chunks.concat(), data);
+    }
+
+    #[test]
+    fn test_split_chunks_resyncs_after_mid_chunk_insertion() {
+        let data: Vec<u8> = (0..4 * AVG_CHUNK_SIZE as u32).map(|i| i as u8).collect();
+        let mut edited = data.clone();
+        edited.splice(MIN_CHUNK_SIZE..MIN_CHUNK_SIZE, vec![0xffu8; 17]);
+
+        let original_digests: HashSet<_> = split_chunks(&data)
+            .into_iter()
+            .map(|chunk| blake3::hash(chunk).to_hex().to_string())
+            .collect();
+        let edited_digests: HashSet<_> = split_chunks(&edited)
+            .into_iter()
+            .map(|chunk| blake3::hash(chunk).to_hex().to_string())
+            .collect();
+
+        assert!(
+            original_digests.intersection(&edited_digests).count() > 0,
+            "a small mid-stream edit should still leave later chunks identical"
+        );
+    }
+
+    #[test]
+    fn test_write_read_chunked_roundtrip() {
+        let fs = memory_fs();
+        let data: Vec<u8> = (0..4 * AVG_CHUNK_SIZE as u32).map(|i| (i * 7) as u8).collect();
+
+        fs.write_chunked("blob", &data).unwrap();
+        let read_back = fs.read_chunked("blob").unwrap();
+
+        assert_eq!(read_back, data);
+    }
+}
@@ -1,6 +1,16 @@
 use std::env;
+use std::sync::Arc;
 
+use anyhow::anyhow;
+use anyhow::ensure;
+use anyhow::Context;
 use anyhow::Result;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::aead::OsRng;
+use chacha20poly1305::AeadCore;
+use chacha20poly1305::KeyInit;
+use chacha20poly1305::XChaCha20Poly1305;
+use chacha20poly1305::XNonce;
 use opendal::layers::AsyncBacktraceLayer;
 use opendal::layers::LoggingLayer;
 use opendal::layers::MinitraceLayer;
@@ -19,11 +29,16 @@ use url::Url;
 pub struct FileStorage {
     fs: BlockingOperator,
     fs_base_url: Url,
+    envelope: Option<Arc<EnvelopeConfig>>,
 }
 
 impl FileStorage {
-    pub fn new(fs: BlockingOperator, fs_base_url: Url) -> Self {
-        Self { fs, fs_base_url }
+    pub fn new(fs: BlockingOperator, fs_base_url: Url, envelope: Option<EnvelopeConfig>) -> Self {
+        Self {
+            fs,
+            fs_base_url,
+            envelope: envelope.map(Arc::new),
+        }
     }
 
     pub fn file_url(&self, filename: &str) -> String {
@@ -35,6 +50,97 @@ impl FileStorage {
     pub fn read_file(&self, filename: &str) -> anyhow::Result<Vec<u8>> {
         Ok(self.fs.read(filename)?.to_vec())
     }
+
+    /// Writes `data` to `name`, transparently compressing and sealing it with an AEAD
+    /// first if an [EnvelopeConfig] was configured. Writes are a no-op envelope-wise
+    /// when none was configured, so existing unencrypted deployments are unaffected.
+    pub fn write_sealed(&self, name: &str, data: &[u8]) -> Result<()> {
+        let payload = match &self.envelope {
+            Some(envelope) => envelope.seal(data)?,
+            None => data.to_vec(),
+        };
+        self.fs.write(name, payload)?;
+        Ok(())
+    }
+
+    /// Reads `name` back, reversing the envelope applied by [FileStorage::write_sealed].
+    pub fn read_sealed(&self, name: &str) -> Result<Vec<u8>> {
+        let raw = self.read_file(name)?;
+        match &self.envelope {
+            Some(envelope) => envelope.open(&raw),
+            None => Ok(raw),
+        }
+    }
+}
+
+/// Magic bytes identifying a blob sealed by [EnvelopeConfig::seal].
+const ENVELOPE_MAGIC: [u8; 4] = *b"GFSE";
+const ENVELOPE_VERSION: u8 = 1;
+/// Header layout: `magic(4) || version(1) || nonce(24) || compressed_flag(1)`.
+const ENVELOPE_HEADER_LEN: usize = ENVELOPE_MAGIC.len() + 1 + 24 + 1;
+
+/// Configuration for the optional compress-then-seal envelope [FileStorage] applies to
+/// blobs written through [FileStorage::write_sealed]. Compression (zstd) runs before
+/// encryption (XChaCha20-Poly1305) so ciphertext size stays close to the compressed size.
+#[derive(Clone, Debug)]
+pub struct EnvelopeConfig {
+    key: [u8; 32],
+    compression_level: i32,
+}
+
+impl EnvelopeConfig {
+    pub fn new(key: [u8; 32], compression_level: i32) -> Self {
+        Self { key, compression_level }
+    }
+
+    fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let (body, compressed) = if self.compression_level != 0 {
+            (
+                zstd::encode_all(plaintext, self.compression_level).context("zstd compress")?,
+                true,
+            )
+        } else {
+            (plaintext.to_vec(), false)
+        };
+
+        let cipher = XChaCha20Poly1305::new((&self.key).into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, body.as_ref())
+            .map_err(|e| anyhow!("seal: {e}"))?;
+
+        let mut out = Vec::with_capacity(ENVELOPE_HEADER_LEN + ciphertext.len());
+        out.extend_from_slice(&ENVELOPE_MAGIC);
+        out.push(ENVELOPE_VERSION);
+        out.extend_from_slice(&nonce);
+        out.push(compressed as u8);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        ensure!(sealed.len() >= ENVELOPE_HEADER_LEN, "sealed blob too short");
+        ensure!(sealed[0..4] == ENVELOPE_MAGIC, "not a sealed blob (bad magic)");
+        ensure!(
+            sealed[4] == ENVELOPE_VERSION,
+            "unsupported envelope version: {}",
+            sealed[4]
+        );
+        let nonce = XNonce::from_slice(&sealed[5..29]);
+        let compressed = sealed[29] != 0;
+        let ciphertext = &sealed[ENVELOPE_HEADER_LEN..];
+
+        let cipher = XChaCha20Poly1305::new((&self.key).into());
+        let body = cipher
+            .decrypt(nonce, ciphertext)
+            .map_err(|e| anyhow!("open: wrong key or corrupted blob: {e}"))?;
+
+        if compressed {
+            zstd::decode_all(body.as_slice()).context("zstd decompress")
+        } else {
+            Ok(body)
+        }
+    }
 }
 
 impl std::ops::Deref for FileStorage {
@@ -45,7 +151,6 @@ impl std::ops::Deref for FileStorage {
     }
 }
 
-#[allow(dead_code)]
 pub static STORAGE_S3_DEFAULT_ENDPOINT: &str = "https://s3.amazonaws.com";
 
 /// Storage params which contains the detailed storage info.
@@ -81,6 +186,38 @@ pub enum StorageParams {
         /// The ExternalId that used for AssumeRole.
         external_id: String,
     },
+    /// A generic read/write HTTP(S) endpoint, for object stores that only expose a
+    /// WebDAV-less HTTP API (or a reverse proxy in front of one).
+    Http {
+        endpoint: String,
+        root: String,
+    },
+    /// An in-memory store. Not durable and not shared across processes; useful for
+    /// unit tests that need a `FileStorage` without touching the filesystem.
+    Memory,
+    /// Google Cloud Storage.
+    Gcs {
+        bucket: String,
+        root: String,
+        credential: String,
+        credential_path: String,
+        endpoint: String,
+    },
+    /// Azure Blob Storage.
+    Azblob {
+        container: String,
+        root: String,
+        endpoint: String,
+        account_name: String,
+        account_key: String,
+    },
+    /// A WebDAV endpoint.
+    WebDav {
+        endpoint: String,
+        root: String,
+        username: String,
+        password: String,
+    },
 }
 
 /// init_operator will init an opendal operator based on storage config.
@@ -88,13 +225,11 @@ pub fn init_operator(cfg: &StorageParams) -> Result<Operator> {
     let op = match &cfg {
         StorageParams::Fs { root } => build_operator(init_fs_operator(root.clone())?)?,
         cfg @ StorageParams::S3 { .. } => build_operator(init_s3_operator(cfg)?)?,
-        // v => {
-        //     return Err(io::Error::new(
-        //         io::ErrorKind::InvalidInput,
-        //         anyhow!("Unsupported storage type: {:?}", v),
-        //     )
-        //     .into());
-        // }
+        cfg @ StorageParams::Http { .. } => build_operator(init_http_operator(cfg)?)?,
+        StorageParams::Memory => build_operator(services::Memory::default())?,
+        cfg @ StorageParams::Gcs { .. } => build_operator(init_gcs_operator(cfg)?)?,
+        cfg @ StorageParams::Azblob { .. } => build_operator(init_azblob_operator(cfg)?)?,
+        cfg @ StorageParams::WebDav { .. } => build_operator(init_webdav_operator(cfg)?)?,
     };
 
     Ok(op)
@@ -199,3 +334,94 @@ fn init_s3_operator(cfg: &StorageParams) -> Result<impl Builder> {
 
     Ok(builder)
 }
+
+/// init_http_operator will init an opendal http operator against a generic read/write
+/// HTTP(S) endpoint.
+fn init_http_operator(cfg: &StorageParams) -> Result<impl Builder> {
+    let StorageParams::Http { endpoint, root } = cfg else {
+        unreachable!();
+    };
+
+    let mut builder = services::Http::default();
+    builder.endpoint(endpoint);
+    builder.root(root);
+
+    Ok(builder)
+}
+
+/// init_gcs_operator will init an opendal gcs operator with input gcs config.
+fn init_gcs_operator(cfg: &StorageParams) -> Result<impl Builder> {
+    let StorageParams::Gcs {
+        bucket,
+        root,
+        credential,
+        credential_path,
+        endpoint,
+    } = cfg
+    else {
+        unreachable!();
+    };
+
+    let mut builder = services::Gcs::default();
+    builder.bucket(bucket);
+    builder.root(root);
+    if !credential.is_empty() {
+        builder.credential(credential);
+    }
+    if !credential_path.is_empty() {
+        builder.credential_path(credential_path);
+    }
+    if !endpoint.is_empty() {
+        builder.endpoint(endpoint);
+    }
+
+    Ok(builder)
+}
+
+/// init_azblob_operator will init an opendal azblob operator with input azure config.
+fn init_azblob_operator(cfg: &StorageParams) -> Result<impl Builder> {
+    let StorageParams::Azblob {
+        container,
+        root,
+        endpoint,
+        account_name,
+        account_key,
+    } = cfg
+    else {
+        unreachable!();
+    };
+
+    let mut builder = services::Azblob::default();
+    builder.container(container);
+    builder.root(root);
+    builder.endpoint(endpoint);
+    builder.account_name(account_name);
+    builder.account_key(account_key);
+
+    Ok(builder)
+}
+
+/// init_webdav_operator will init an opendal webdav operator.
+fn init_webdav_operator(cfg: &StorageParams) -> Result<impl Builder> {
+    let StorageParams::WebDav {
+        endpoint,
+        root,
+        username,
+        password,
+    } = cfg
+    else {
+        unreachable!();
+    };
+
+    let mut builder = services::Webdav::default();
+    builder.endpoint(endpoint);
+    builder.root(root);
+    if !username.is_empty() {
+        builder.username(username);
+    }
+    if !password.is_empty() {
+        builder.password(password);
+    }
+
+    Ok(builder)
+}
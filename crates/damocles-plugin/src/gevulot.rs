@@ -1,15 +1,77 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
 use anyhow::Result;
 use async_trait::async_trait;
 use gevulot_node::types::transaction::WorkflowStep;
 use gevulot_node::types::Hash;
+use tokio::sync::mpsc;
 
 pub mod local;
 pub mod rpc;
 
+/// Scheduling priority and hardware requirements attached to a workflow submission.
+///
+/// `priority` orders submissions when the executor's worker pool is saturated (higher
+/// runs first); `platform` is matched against worker capabilities so, e.g., C2 proving
+/// can require a GPU-equipped node while cheaper verification work lands anywhere.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionPolicy {
+    pub priority: i32,
+    pub platform: Platform,
+}
+
+impl ExecutionPolicy {
+    pub fn new(priority: i32, platform: Platform) -> Self {
+        Self { priority, platform }
+    }
+}
+
+/// Key/value worker-capability requirements an executor matches a workflow submission
+/// against, e.g. `gpu=true`, `min_vram_mb=16384`, `min_ram_mb=32768`, `min_cores=8`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Platform(BTreeMap<String, String>);
+
+impl Platform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.0.insert(key.into(), value.into());
+        self
+    }
+
+    /// Shorthand for a GPU-only requirement, used by GPU-bound proving stages like C2.
+    pub fn requires_gpu() -> Self {
+        Self::new().with("gpu", "true")
+    }
+
+    pub fn properties(&self) -> &BTreeMap<String, String> {
+        &self.0
+    }
+}
+
+/// Lifecycle of a workflow submitted via [`GevulotExecutor::run_program`], as reported by
+/// [`GevulotExecutor::query_status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxStatus {
+    /// The node has accepted the transaction but has not started executing it yet.
+    Pending,
+    /// At least one workflow step is currently executing.
+    Running,
+    /// Every workflow step finished and a proof is available via `query_proof`.
+    Succeeded,
+    /// A workflow step failed; `reason` is the node-reported failure message.
+    Failed { reason: String },
+}
+
 #[async_trait]
 pub trait GevulotExecutor {
-    async fn run_program(&self, steps: Vec<WorkflowStep>) -> Result<Hash>;
-    async fn query_proof(&self, hash: &Hash) -> Result<Option<String>>;
+    async fn run_program(&self, steps: Vec<WorkflowStep>, policy: ExecutionPolicy) -> Result<Hash>;
+    async fn query_status(&self, hash: &Hash) -> Result<TxStatus>;
+    /// Fetches the verifier step's raw output bytes, once available.
+    async fn query_proof(&self, hash: &Hash) -> Result<Option<Vec<u8>>>;
 }
 
 #[derive(Debug, Clone)]
@@ -24,16 +86,358 @@ where
     L: GevulotExecutor + Sync,
     R: GevulotExecutor + Sync,
 {
-    async fn run_program(&self, steps: Vec<WorkflowStep>) -> Result<Hash> {
+    async fn run_program(&self, steps: Vec<WorkflowStep>, policy: ExecutionPolicy) -> Result<Hash> {
+        match self {
+            Either::Left(left) => left.run_program(steps, policy).await,
+            Either::Right(right) => right.run_program(steps, policy).await,
+        }
+    }
+    async fn query_status(&self, hash: &Hash) -> Result<TxStatus> {
         match self {
-            Either::Left(left) => left.run_program(steps).await,
-            Either::Right(right) => right.run_program(steps).await,
+            Either::Left(left) => left.query_status(hash).await,
+            Either::Right(right) => right.query_status(hash).await,
         }
     }
-    async fn query_proof(&self, hash: &Hash) -> Result<Option<String>> {
+    async fn query_proof(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
         match self {
             Either::Left(left) => left.query_proof(hash).await,
             Either::Right(right) => right.query_proof(hash).await,
         }
     }
 }
+
+/// How a multi-node combinator picks which of several equivalent Gevulot nodes a
+/// result comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionPolicy {
+    /// Try nodes one at a time, in configured order, and stop at the first success.
+    FirstSuccess,
+    /// Submit/poll every node concurrently and return whichever answers first.
+    RaceAll,
+}
+
+/// Combinator over `Vec<E>` equivalent Gevulot node executors that tolerates a single
+/// node being transiently down, slow, or dropping a submitted job on the floor.
+///
+/// `run_program` submits according to `policy`. `query_proof` always races every node
+/// regardless of `policy`, since a node that simply hasn't produced the proof yet
+/// returns `Ok(None)` rather than an error, and "first success" over that would just
+/// return the first node's `None` instead of waiting on the others to finish the job.
+/// When every node fails a call, the returned error records each node's attempt error.
+#[derive(Clone)]
+pub struct Failover<E> {
+    nodes: Vec<E>,
+    policy: SelectionPolicy,
+}
+
+impl<E> Failover<E> {
+    pub fn new(nodes: Vec<E>, policy: SelectionPolicy) -> Self {
+        assert!(!nodes.is_empty(), "Failover requires at least one node");
+        Self { nodes, policy }
+    }
+}
+
+#[async_trait]
+impl<E: GevulotExecutor + Clone + Send + Sync + 'static> GevulotExecutor for Failover<E> {
+    async fn run_program(&self, steps: Vec<WorkflowStep>, policy: ExecutionPolicy) -> Result<Hash> {
+        match self.policy {
+            SelectionPolicy::FirstSuccess => {
+                let mut errors = Vec::with_capacity(self.nodes.len());
+                for node in &self.nodes {
+                    match node.run_program(steps.clone(), policy.clone()).await {
+                        Ok(hash) => return Ok(hash),
+                        Err(err) => errors.push(err),
+                    }
+                }
+                Err(all_nodes_failed("submit the workflow", errors))
+            }
+            SelectionPolicy::RaceAll => {
+                race_all(&self.nodes, "submit the workflow", |node| {
+                    let steps = steps.clone();
+                    let policy = policy.clone();
+                    async move { node.run_program(steps, policy).await }
+                })
+                .await
+            }
+        }
+    }
+
+    async fn query_status(&self, hash: &Hash) -> Result<TxStatus> {
+        let hash = *hash;
+        let results =
+            poll_all(&self.nodes, |node| async move { node.query_status(&hash).await }).await;
+        best_status(results)
+    }
+
+    async fn query_proof(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
+        let hash = *hash;
+        let results = poll_all(&self.nodes, |node| async move { node.query_proof(&hash).await })
+            .await;
+
+        let mut errors = Vec::with_capacity(results.len());
+        for result in results {
+            match result {
+                Ok(Some(proof)) => return Ok(Some(proof)),
+                Ok(None) => {}
+                Err(err) => errors.push(err),
+            }
+        }
+
+        if errors.len() == self.nodes.len() {
+            Err(all_nodes_failed("query the proof", errors))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Combinator over `Vec<E>` equivalent Gevulot nodes that only accepts a result once `k`
+/// of the `n` nodes agree on it byte-for-byte, guarding against a single compromised or
+/// misbehaving node returning a bogus proof.
+#[derive(Clone)]
+pub struct Quorum<E> {
+    nodes: Vec<E>,
+    k: usize,
+}
+
+impl<E> Quorum<E> {
+    pub fn new(nodes: Vec<E>, k: usize) -> Self {
+        assert!(!nodes.is_empty(), "Quorum requires at least one node");
+        assert!(
+            k > 0 && k <= nodes.len(),
+            "Quorum requires 0 < k <= nodes.len()"
+        );
+        Self { nodes, k }
+    }
+}
+
+#[async_trait]
+impl<E: GevulotExecutor + Clone + Send + Sync + 'static> GevulotExecutor for Quorum<E> {
+    async fn run_program(&self, steps: Vec<WorkflowStep>, policy: ExecutionPolicy) -> Result<Hash> {
+        let results = poll_all(&self.nodes, |node| {
+            let steps = steps.clone();
+            let policy = policy.clone();
+            async move { node.run_program(steps, policy).await }
+        })
+        .await;
+
+        let mut agreement: Vec<Hash> = Vec::new();
+        let mut errors = Vec::with_capacity(results.len());
+        for result in results {
+            match result {
+                Ok(hash) => agreement.push(hash),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        agreement
+            .iter()
+            .find(|candidate| agreement.iter().filter(|h| *h == *candidate).count() >= self.k)
+            .copied()
+            .ok_or_else(|| {
+                all_nodes_failed(
+                    &format!(
+                        "reach {}-of-{} agreement submitting the workflow",
+                        self.k,
+                        self.nodes.len()
+                    ),
+                    errors,
+                )
+            })
+    }
+
+    async fn query_status(&self, hash: &Hash) -> Result<TxStatus> {
+        let hash = *hash;
+        let results =
+            poll_all(&self.nodes, |node| async move { node.query_status(&hash).await }).await;
+        best_status(results)
+    }
+
+    async fn query_proof(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
+        let hash = *hash;
+        let results = poll_all(&self.nodes, |node| async move { node.query_proof(&hash).await })
+            .await;
+
+        let mut proofs: Vec<Vec<u8>> = Vec::new();
+        let mut errors = Vec::with_capacity(results.len());
+        for result in results {
+            match result {
+                Ok(Some(proof)) => proofs.push(proof),
+                Ok(None) => {}
+                Err(err) => errors.push(err),
+            }
+        }
+
+        if errors.len() == self.nodes.len() {
+            return Err(all_nodes_failed("query the proof", errors));
+        }
+
+        let agreed = proofs
+            .iter()
+            .find(|candidate| proofs.iter().filter(|p| *p == *candidate).count() >= self.k)
+            .cloned();
+        Ok(agreed)
+    }
+}
+
+/// Runs `make_fut(node)` against every node concurrently and returns the first `Ok`,
+/// failing with every node's error only once all of them have failed.
+async fn race_all<E, F, Fut, T>(nodes: &[E], action: &str, make_fut: F) -> Result<T>
+where
+    E: Clone + Send + 'static,
+    F: Fn(E) -> Fut,
+    Fut: std::future::Future<Output = Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel(nodes.len().max(1));
+    for node in nodes.iter().cloned() {
+        let tx = tx.clone();
+        let fut = make_fut(node);
+        tokio::spawn(async move {
+            let _ = tx.send(fut.await).await;
+        });
+    }
+    drop(tx);
+
+    let mut errors = Vec::with_capacity(nodes.len());
+    while let Some(result) = rx.recv().await {
+        match result {
+            Ok(value) => return Ok(value),
+            Err(err) => errors.push(err),
+        }
+    }
+    Err(all_nodes_failed(action, errors))
+}
+
+/// Runs `make_fut(node)` against every node concurrently and waits for all of them,
+/// preserving each node's own `Result`.
+async fn poll_all<E, F, Fut, T>(nodes: &[E], make_fut: F) -> Vec<Result<T>>
+where
+    E: Clone + Send + 'static,
+    F: Fn(E) -> Fut,
+    Fut: std::future::Future<Output = Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel(nodes.len().max(1));
+    for node in nodes.iter().cloned() {
+        let tx = tx.clone();
+        let fut = make_fut(node);
+        tokio::spawn(async move {
+            let _ = tx.send(fut.await).await;
+        });
+    }
+    drop(tx);
+
+    let mut results = Vec::with_capacity(nodes.len());
+    while let Some(result) = rx.recv().await {
+        results.push(result);
+    }
+    results
+}
+
+/// Reduces every node's reported status to a single one, preferring whichever node made
+/// the most progress: a proof from any node means the workflow is done even if another
+/// node dropped it, and a node still pending or running should not be masked by another
+/// node reporting a stale failure.
+fn best_status(results: Vec<Result<TxStatus>>) -> Result<TxStatus> {
+    let mut statuses = Vec::with_capacity(results.len());
+    let mut errors = Vec::with_capacity(results.len());
+    for result in results {
+        match result {
+            Ok(status) => statuses.push(status),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if statuses.is_empty() {
+        return Err(all_nodes_failed("query the status", errors));
+    }
+    if statuses.iter().any(|s| *s == TxStatus::Succeeded) {
+        return Ok(TxStatus::Succeeded);
+    }
+    if statuses.iter().any(|s| *s == TxStatus::Running) {
+        return Ok(TxStatus::Running);
+    }
+    if statuses.iter().any(|s| *s == TxStatus::Pending) {
+        return Ok(TxStatus::Pending);
+    }
+    Ok(statuses.into_iter().next().expect("checked non-empty above"))
+}
+
+fn all_nodes_failed(action: &str, errors: Vec<anyhow::Error>) -> anyhow::Error {
+    let mut msg = format!(
+        "all {} configured gevulot nodes failed to {action}",
+        errors.len()
+    );
+    for (idx, err) in errors.iter().enumerate() {
+        msg.push_str(&format!("\n  node[{idx}]: {err:#}"));
+    }
+    anyhow!(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A node that always reports the same fixed `hash` for `run_program` and the same
+    /// fixed `proof` (if any) for `query_proof`, simulating a node signing with its own key.
+    #[derive(Clone)]
+    struct FixedNode {
+        hash: Hash,
+        proof: Option<Vec<u8>>,
+    }
+
+    #[async_trait]
+    impl GevulotExecutor for FixedNode {
+        async fn run_program(&self, _steps: Vec<WorkflowStep>, _policy: ExecutionPolicy) -> Result<Hash> {
+            Ok(self.hash)
+        }
+        async fn query_status(&self, _hash: &Hash) -> Result<TxStatus> {
+            Ok(TxStatus::Succeeded)
+        }
+        async fn query_proof(&self, _hash: &Hash) -> Result<Option<Vec<u8>>> {
+            Ok(self.proof.clone())
+        }
+    }
+
+    fn run<F: std::future::Future>(fut: F) -> F::Output {
+        tokio::runtime::Runtime::new().unwrap().block_on(fut)
+    }
+
+    #[test]
+    fn test_quorum_run_program_rejects_disagreement() {
+        let nodes = vec![
+            FixedNode { hash: Hash::random(&mut rand::thread_rng()), proof: None },
+            FixedNode { hash: Hash::random(&mut rand::thread_rng()), proof: None },
+        ];
+        let quorum = Quorum::new(nodes, 2);
+
+        let result = run(quorum.run_program(Vec::new(), ExecutionPolicy::default()));
+        assert!(result.is_err(), "distinct per-node hashes must not reach k-of-2 agreement");
+    }
+
+    #[test]
+    fn test_quorum_run_program_accepts_agreement() {
+        let hash = Hash::random(&mut rand::thread_rng());
+        let nodes = vec![
+            FixedNode { hash, proof: None },
+            FixedNode { hash, proof: None },
+        ];
+        let quorum = Quorum::new(nodes, 2);
+
+        let result = run(quorum.run_program(Vec::new(), ExecutionPolicy::default()));
+        assert_eq!(result.unwrap().to_string(), hash.to_string());
+    }
+
+    #[test]
+    fn test_quorum_query_proof_rejects_disagreement() {
+        let nodes = vec![
+            FixedNode { hash: Hash::random(&mut rand::thread_rng()), proof: Some(vec![1]) },
+            FixedNode { hash: Hash::random(&mut rand::thread_rng()), proof: Some(vec![2]) },
+        ];
+        let quorum = Quorum::new(nodes, 2);
+
+        let result = run(quorum.query_proof(&Hash::random(&mut rand::thread_rng())));
+        assert_eq!(result.unwrap(), None, "distinct per-node proofs must not reach k-of-2 agreement");
+    }
+}
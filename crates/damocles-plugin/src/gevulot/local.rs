@@ -11,16 +11,17 @@ use gevulot_fil::C2Input;
 use gevulot_node::types::transaction::ProgramData;
 use gevulot_node::types::transaction::WorkflowStep;
 use gevulot_node::types::Hash;
-use hex::ToHex;
 use tokio::sync::Mutex;
 use tokio::task::spawn_blocking;
 
+use super::ExecutionPolicy;
 use super::GevulotExecutor;
+use super::TxStatus;
 use crate::filestorage::FileStorage;
 
 #[derive(Clone)]
 pub struct GevulotLocalExecutor {
-    proofs: Arc<Mutex<HashMap<Hash, String>>>,
+    proofs: Arc<Mutex<HashMap<Hash, Vec<u8>>>>,
     fs: FileStorage,
 }
 
@@ -35,12 +36,12 @@ impl GevulotLocalExecutor {
 
 #[async_trait]
 impl GevulotExecutor for GevulotLocalExecutor {
-    async fn run_program(&self, steps: Vec<WorkflowStep>) -> Result<Hash> {
+    async fn run_program(&self, steps: Vec<WorkflowStep>, _policy: ExecutionPolicy) -> Result<Hash> {
         let program_data = steps[0].inputs[0].clone();
         let fs = self.fs.clone();
         let proof = spawn_blocking(move || {
             let c2_input_data = match program_data {
-                ProgramData::Input { file_name, .. } => fs.read_file(&file_name)?,
+                ProgramData::Input { file_name, .. } => fs.read_chunked(&file_name)?,
                 _ => {
                     return Err(anyhow!("invlid workflow"));
                 }
@@ -56,7 +57,25 @@ impl GevulotExecutor for GevulotLocalExecutor {
                 } => {
                     let c2out = seal::seal_commit_phase2(c1out, prover_id, sector_id)
                         .context("run seal_commit_phase2")?;
-                    Ok(c2out.proof.encode_hex())
+                    Ok(c2out.proof)
+                }
+                C2Input::AggregateV0 {
+                    registered_proof,
+                    registered_aggregation,
+                    sectors,
+                } => {
+                    let comm_rs: Vec<_> = sectors.iter().map(|s| s.comm_r).collect();
+                    let seeds: Vec<_> = sectors.iter().map(|s| s.seed).collect();
+                    let commit_outputs: Vec<_> = sectors.into_iter().map(|s| s.c2out).collect();
+
+                    seal::aggregate_seal_commit_proofs(
+                        registered_proof,
+                        registered_aggregation,
+                        &comm_rs,
+                        &seeds,
+                        &commit_outputs,
+                    )
+                    .context("run aggregate_seal_commit_proofs")
                 }
             }
         })
@@ -68,7 +87,15 @@ impl GevulotExecutor for GevulotLocalExecutor {
         Ok(hash)
     }
 
-    async fn query_proof(&self, hash: &Hash) -> Result<Option<String>> {
-        Ok(self.proofs.lock().await.get(hash).map(ToOwned::to_owned))
+    async fn query_status(&self, hash: &Hash) -> Result<TxStatus> {
+        Ok(if self.proofs.lock().await.contains_key(hash) {
+            TxStatus::Succeeded
+        } else {
+            TxStatus::Pending
+        })
+    }
+
+    async fn query_proof(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
+        Ok(self.proofs.lock().await.get(hash).cloned())
     }
 }
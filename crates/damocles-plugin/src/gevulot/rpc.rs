@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
 use anyhow::anyhow;
+use anyhow::Context;
 use anyhow::Result;
 use async_trait::async_trait;
+use base64::Engine;
+use gevulot_fil::calc_checksum;
 use gevulot_fil::SecretKey;
 use gevulot_node::rpc_client::RpcClient;
 use gevulot_node::types::rpc::TxRpcPayload;
@@ -13,7 +16,9 @@ use gevulot_node::types::Hash;
 use gevulot_node::types::Transaction;
 use zeroize::Zeroizing;
 
+use super::ExecutionPolicy;
 use super::GevulotExecutor;
+use super::TxStatus;
 use crate::filestorage::FileStorage;
 
 #[derive(Clone)]
@@ -35,7 +40,11 @@ impl GevulotRpcExecutor {
 
 #[async_trait]
 impl GevulotExecutor for GevulotRpcExecutor {
-    async fn run_program(&self, steps: Vec<WorkflowStep>) -> Result<Hash> {
+    async fn run_program(&self, steps: Vec<WorkflowStep>, policy: ExecutionPolicy) -> Result<Hash> {
+        // The node's scheduler does not yet accept priority/platform hints over the
+        // wire; `policy` is threaded through the trait so callers and the in-process
+        // combinators can express it, ready for when the RPC payload gains a field for it.
+        let _ = policy;
         let tx = Transaction::new(
             Payload::Run {
                 workflow: Workflow { steps },
@@ -65,7 +74,22 @@ impl GevulotExecutor for GevulotRpcExecutor {
         Ok(tx.hash)
     }
 
-    async fn query_proof(&self, tx_hash: &Hash) -> Result<Option<String>> {
+    async fn query_status(&self, tx_hash: &Hash) -> Result<TxStatus> {
+        let tx = self
+            .client
+            .get_transaction(&tx_hash)
+            .await
+            .map_err(|err| anyhow!("error during send get_transaction from the node: {err}"))?;
+
+        Ok(match tx.payload {
+            TxRpcPayload::Proof { .. } => TxStatus::Succeeded,
+            TxRpcPayload::Failed { reason } => TxStatus::Failed { reason },
+            TxRpcPayload::Running => TxStatus::Running,
+            _ => TxStatus::Pending,
+        })
+    }
+
+    async fn query_proof(&self, tx_hash: &Hash) -> Result<Option<Vec<u8>>> {
         let tx = self
             .client
             .get_transaction(&tx_hash)
@@ -73,7 +97,20 @@ impl GevulotExecutor for GevulotRpcExecutor {
             .map_err(|err| anyhow!("error during send get_transaction from the node: {err}"))?;
 
         match tx.payload {
-            TxRpcPayload::Proof { proof, .. } => Ok(Some(proof)),
+            TxRpcPayload::Proof { proof, checksum, .. } => {
+                let proof = base64::engine::general_purpose::STANDARD
+                    .decode(proof)
+                    .context("decode base64 proof from node")?;
+
+                let computed = calc_checksum(&proof).to_string();
+                if computed != checksum {
+                    return Err(anyhow!(
+                        "proof checksum mismatch: node reported {checksum}, computed {computed}"
+                    ));
+                }
+
+                Ok(Some(proof))
+            }
             _ => Ok(None),
         }
     }
@@ -6,6 +6,7 @@ use std::fs::File;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -15,15 +16,25 @@ use filestorage::FileStorage;
 use gevulot::local::GevulotLocalExecutor;
 use gevulot::rpc::GevulotRpcExecutor;
 use gevulot::Either;
+use gevulot::ExecutionPolicy;
+use gevulot::Failover;
+use gevulot::Platform;
+use gevulot::SelectionPolicy;
 use gevulot_fil::codec::decode_from;
 use gevulot_fil::C2Input;
 use gevulot_fil::SecretKey;
 use gevulot_fil::WindowPoStPhase2Input;
+use gevulot_fil::WinningPoStPhase2Input;
 use gevulot_node::rpc_client::RpcClient;
 use gevulot_node::types::Hash;
 use panic_hook::install_panic_hook;
+use processor::aggregate_c2::AggregateC2Processor;
 use processor::c2::C2Processor;
+use processor::c2::ExecBackend;
+use processor::c2::PollConfig;
+use processor::c2::VerifyConfig;
 use processor::windowpost::WindowPoStProcessor;
+use processor::winningpost::WinningPoStProcessor;
 use tokio::runtime::Builder;
 use tracing::info;
 use url::Url;
@@ -33,11 +44,13 @@ use vc_processors::core::DaemonProcessor;
 use warp::Filter;
 use zeroize::Zeroizing;
 
+mod chunking;
 mod filestorage;
 mod gevulot;
 mod logging;
 mod panic_hook;
 mod processor;
+mod server;
 mod util;
 mod version;
 
@@ -53,10 +66,19 @@ struct Cli {
     /// RPC url of the Gevulot node
     #[arg(short, long, env, default_value = "http://localhost:9944")]
     rpc_url: String,
+    /// Additional Gevulot node RPC urls to submit/poll alongside `--rpc-url` for
+    /// failover, so a single node outage doesn't strand a proving task.
+    #[arg(long, env, value_delimiter = ',')]
+    rpc_failover_urls: Vec<String>,
+    /// How to pick among multiple configured Gevulot node endpoints, when
+    /// `--rpc-failover-urls` is set.
+    #[arg(long, env, default_value = "first-success")]
+    rpc_selection_policy: RpcSelectionPolicy,
     /// Mock mode
     #[arg(long, default_value = "false")]
     mock: bool,
-    /// Private key file path to sign Tx.
+    /// Private key file path to sign Tx. May be either a raw secp256k1 key or
+    /// a Web3 Secret Storage encrypted keystore produced by `keygen`/`import`.
     #[clap(
         short,
         long,
@@ -65,16 +87,241 @@ struct Cli {
         value_name = "KEY FILE PATH"
     )]
     keyfile: PathBuf,
+    /// Path to a file holding the password for an encrypted `keyfile`. If the
+    /// keyfile is a plaintext key this is ignored. If omitted, the password is
+    /// read interactively.
+    #[arg(long, env)]
+    keyfile_password_file: Option<PathBuf>,
 
     #[arg(long, env)]
     fileserver_path: PathBuf,
     #[arg(long, env, default_value = DEFAULT_FILE_SERVER_URL)]
     fileserver_base_url: Url,
 
+    #[command(flatten)]
+    storage: StorageArgs,
+
+    #[command(flatten)]
+    envelope: EnvelopeArgs,
+
+    #[command(flatten)]
+    policy: PolicyArgs,
+
+    #[command(flatten)]
+    poll: PollArgs,
+
+    /// Locally re-run `verify_seal`/`verify_aggregate_seal_commit_proofs` against the
+    /// downloaded c2 proof before returning it, so a faulty or malicious prover node
+    /// can't poison the pipeline with an invalid proof.
+    #[arg(long, env, default_value = "false")]
+    verify_proof_locally: bool,
+
+    /// Run c2 entirely in-process via `seal_commit_phase2`, skipping Gevulot workflow
+    /// dispatch altogether. Lets the crate be exercised in tests and CI with no Gevulot
+    /// node, mock or otherwise.
+    #[arg(long, env, default_value = "false")]
+    local_c2: bool,
+    /// When dispatching c2 to Gevulot, fall back to local execution if submitting the
+    /// workflow keeps failing after `--poll-max-transient-retries`. Ignored when
+    /// `--local-c2` is set.
+    #[arg(long, env, default_value = "false")]
+    fallback_to_local_c2: bool,
+
     #[command(subcommand)]
     commands: Commands,
 }
 
+/// CLI/env surface for the [PollConfig] used by [C2Processor::exec]'s poll loop.
+#[derive(clap::Args)]
+struct PollArgs {
+    /// Delay before the first poll for a c2 proof, and the starting point for backoff.
+    #[arg(long, env, default_value = "5")]
+    poll_initial_interval_secs: u64,
+    /// Upper bound the backed-off poll interval is capped at.
+    #[arg(long, env, default_value = "60")]
+    poll_max_interval_secs: u64,
+    /// Multiplier applied to the poll interval after each unsuccessful poll.
+    #[arg(long, env, default_value = "1.5")]
+    poll_backoff_factor: f64,
+    /// Total time budget for submitting the workflow and polling it to completion.
+    #[arg(long, env, default_value = "7200")]
+    poll_overall_timeout_secs: u64,
+    /// How many consecutive transient `run_program`/`query_proof` errors are retried
+    /// before the c2 task fails.
+    #[arg(long, env, default_value = "5")]
+    poll_max_transient_retries: u32,
+}
+
+fn build_poll_config(args: &PollArgs) -> PollConfig {
+    PollConfig {
+        initial_interval: Duration::from_secs(args.poll_initial_interval_secs),
+        max_interval: Duration::from_secs(args.poll_max_interval_secs),
+        backoff_factor: args.poll_backoff_factor,
+        overall_timeout: Duration::from_secs(args.poll_overall_timeout_secs),
+        max_transient_retries: args.poll_max_transient_retries,
+    }
+}
+
+/// CLI/env surface for the [ExecutionPolicy] attached to every workflow this process
+/// submits, letting an operator running a mix of urgent and batch jobs steer scheduling
+/// without a config file.
+#[derive(clap::Args)]
+struct PolicyArgs {
+    /// Scheduling priority for submitted workflows; higher values run first when the
+    /// executor's worker pool is saturated.
+    #[arg(long, env, default_value_t = 0)]
+    priority: i32,
+    /// Comma-separated `key=value` platform/hardware requirements (e.g.
+    /// `gpu=true,min_vram_mb=16384`) matched against worker capabilities when
+    /// scheduling the workflow.
+    #[arg(long, env, value_delimiter = ',')]
+    platform: Vec<String>,
+}
+
+/// Picks the [ExecBackend] for a [C2Processor] per `--local-c2`.
+fn build_c2_backend<G>(cli: &Cli, exec: G) -> ExecBackend<G> {
+    if cli.local_c2 {
+        ExecBackend::Local
+    } else {
+        ExecBackend::Gevulot(exec)
+    }
+}
+
+fn build_execution_policy(args: &PolicyArgs) -> Result<ExecutionPolicy> {
+    let mut platform = Platform::new();
+    for entry in &args.platform {
+        let (key, value) = entry
+            .split_once('=')
+            .with_context(|| format!("invalid --platform entry {entry:?}, expected key=value"))?;
+        platform = platform.with(key, value);
+    }
+    Ok(ExecutionPolicy::new(args.priority, platform))
+}
+
+/// Like [build_execution_policy], but defaults to [Platform::requires_gpu] when the
+/// operator didn't pass any `--platform` entries, since C2/aggregate-C2 proving is
+/// GPU-bound and must land on a GPU-equipped node. An explicit `--platform` still wins,
+/// so an operator who wants different constraints (or none) can still set them; it's
+/// only the *default* that differs from the cheaper, anywhere-is-fine verification work.
+fn build_c2_execution_policy(args: &PolicyArgs) -> Result<ExecutionPolicy> {
+    let policy = build_execution_policy(args)?;
+    if policy.platform.properties().is_empty() {
+        Ok(ExecutionPolicy::new(policy.priority, Platform::requires_gpu()))
+    } else {
+        Ok(policy)
+    }
+}
+
+/// CLI/env surface for the optional [filestorage::EnvelopeConfig] sealed-storage layer.
+#[derive(clap::Args)]
+struct EnvelopeArgs {
+    /// Path to a 32-byte (raw or hex-encoded) key used to encrypt proof inputs/outputs
+    /// written through the file storage backend. If omitted, blobs are stored in plaintext.
+    #[arg(long, env)]
+    envelope_key_file: Option<PathBuf>,
+    /// zstd compression level to apply before sealing, or 0 to disable compression.
+    #[arg(long, env, default_value = "0")]
+    envelope_compression_level: i32,
+}
+
+/// CLI/env surface for [filestorage::StorageParams]. `--storage-backend` selects which
+/// of the flattened backend-specific groups below is actually used; the others are
+/// ignored. This lets provers proving against a shared remote store (instead of the
+/// single-host directory served by `fileserver_path`) configure it without a config file.
+#[derive(clap::Args)]
+struct StorageArgs {
+    /// Which storage backend to use for proof inputs/outputs.
+    #[arg(long, env, default_value = "fs")]
+    storage_backend: StorageBackend,
+
+    #[arg(long, env)]
+    s3_endpoint_url: Option<String>,
+    #[arg(long, env, default_value = "")]
+    s3_region: String,
+    #[arg(long, env)]
+    s3_bucket: Option<String>,
+    #[arg(long, env, default_value = "")]
+    s3_access_key_id: String,
+    #[arg(long, env, default_value = "")]
+    s3_secret_access_key: String,
+    #[arg(long, env, default_value = "")]
+    s3_security_token: String,
+    #[arg(long, env, default_value = "")]
+    s3_root: String,
+    #[arg(long, env, default_value = "false")]
+    s3_disable_credential_loader: bool,
+    #[arg(long, env, default_value = "false")]
+    s3_enable_virtual_host_style: bool,
+    #[arg(long, env, default_value = "")]
+    s3_role_arn: String,
+    #[arg(long, env, default_value = "")]
+    s3_external_id: String,
+
+    #[arg(long, env)]
+    http_endpoint: Option<String>,
+    #[arg(long, env, default_value = "")]
+    http_root: String,
+
+    #[arg(long, env)]
+    gcs_bucket: Option<String>,
+    #[arg(long, env, default_value = "")]
+    gcs_root: String,
+    #[arg(long, env, default_value = "")]
+    gcs_credential: String,
+    #[arg(long, env, default_value = "")]
+    gcs_credential_path: String,
+    #[arg(long, env, default_value = "")]
+    gcs_endpoint: String,
+
+    #[arg(long, env)]
+    azblob_container: Option<String>,
+    #[arg(long, env, default_value = "")]
+    azblob_root: String,
+    #[arg(long, env, default_value = "")]
+    azblob_endpoint: String,
+    #[arg(long, env, default_value = "")]
+    azblob_account_name: String,
+    #[arg(long, env, default_value = "")]
+    azblob_account_key: String,
+
+    #[arg(long, env)]
+    webdav_endpoint: Option<String>,
+    #[arg(long, env, default_value = "")]
+    webdav_root: String,
+    #[arg(long, env, default_value = "")]
+    webdav_username: String,
+    #[arg(long, env, default_value = "")]
+    webdav_password: String,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum RpcSelectionPolicy {
+    /// Submit/poll the configured nodes in order and stop at the first success.
+    FirstSuccess,
+    /// Submit/poll every configured node concurrently and use whichever answers first.
+    RaceAll,
+}
+
+impl From<RpcSelectionPolicy> for SelectionPolicy {
+    fn from(value: RpcSelectionPolicy) -> Self {
+        match value {
+            RpcSelectionPolicy::FirstSuccess => SelectionPolicy::FirstSuccess,
+            RpcSelectionPolicy::RaceAll => SelectionPolicy::RaceAll,
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum StorageBackend {
+    Fs,
+    S3,
+    Http,
+    Memory,
+    Gcs,
+    Azblob,
+    WebDav,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     #[command(subcommand)]
@@ -86,6 +333,44 @@ enum Commands {
     },
     #[command(subcommand)]
     Exec(ExecCommands),
+    /// Run an HTTP JSON-RPC control/status server so external schedulers can
+    /// submit C2/WindowPoSt tasks and poll for their results asynchronously.
+    Server {
+        /// Listen on the given IP:port
+        #[arg(short, long, env, default_value = "127.0.0.1:31314")]
+        listen: SocketAddr,
+        #[arg(long, env, value_parser=parse_hash)]
+        c2_prover_program: Hash,
+        #[arg(long, env, value_parser=parse_hash)]
+        c2_verifier_program: Hash,
+        #[arg(long, env, value_parser=parse_hash)]
+        window_post_prover_program: Hash,
+        #[arg(long, env, value_parser=parse_hash)]
+        window_post_verifier_program: Hash,
+    },
+    /// Generate a new signing key and write it as an encrypted keystore.
+    Keygen {
+        /// Output path for the encrypted keystore file.
+        #[arg(long, env)]
+        out: PathBuf,
+        /// Path to a file holding the new keystore's password. If omitted,
+        /// the password is read interactively.
+        #[arg(long, env)]
+        password_file: Option<PathBuf>,
+    },
+    /// Encrypt an existing raw key file into a Web3 Secret Storage keystore.
+    Import {
+        /// Path to a raw (unencrypted) secp256k1 key file to import.
+        #[arg(long, env)]
+        keyfile: PathBuf,
+        /// Output path for the encrypted keystore file.
+        #[arg(long, env)]
+        out: PathBuf,
+        /// Path to a file holding the new keystore's password. If omitted,
+        /// the password is read interactively.
+        #[arg(long, env)]
+        password_file: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -104,6 +389,20 @@ enum ProcessorCommands {
         #[arg(long, env, value_parser=parse_hash)]
         verifier_program: Hash,
     },
+    #[command(name = "winning_post", about = "gevulot winningPoST processor")]
+    WinningPoST {
+        #[arg(long, env, value_parser=parse_hash)]
+        prover_program: Hash,
+        #[arg(long, env, value_parser=parse_hash)]
+        verifier_program: Hash,
+    },
+    #[command(name = "aggregate_c2", about = "gevulot SnarkPack aggregate c2 processor")]
+    AggregateC2 {
+        #[arg(long, env, value_parser=parse_hash)]
+        prover_program: Hash,
+        #[arg(long, env, value_parser=parse_hash)]
+        verifier_program: Hash,
+    },
 }
 
 #[derive(Subcommand)]
@@ -129,6 +428,30 @@ enum ExecCommands {
         #[arg(long, env)]
         input_file: PathBuf,
     },
+    #[command(
+        name = "winning_post",
+        about = "manually execute winningPoST on gevulot network"
+    )]
+    WinningPoST {
+        #[arg(long, env, value_parser=parse_hash)]
+        prover_program: Hash,
+        #[arg(long, env, value_parser=parse_hash)]
+        verifier_program: Hash,
+        #[arg(long, env)]
+        input_file: PathBuf,
+    },
+    #[command(
+        name = "aggregate_c2",
+        about = "manually execute a SnarkPack aggregate c2 on gevulot network"
+    )]
+    AggregateC2 {
+        #[arg(long, env, value_parser=parse_hash)]
+        prover_program: Hash,
+        #[arg(long, env, value_parser=parse_hash)]
+        verifier_program: Hash,
+        #[arg(long, env)]
+        input_file: PathBuf,
+    },
 }
 
 pub fn main() -> Result<()> {
@@ -150,8 +473,23 @@ pub fn main() -> Result<()> {
         }) => {
             let fs = create_fs(&cli)?;
             let exec = create_gevulot_executor(&cli, fs.clone())?;
+            let policy = build_c2_execution_policy(&cli.policy)?;
+            let poll = build_poll_config(&cli.poll);
+            let verify = VerifyConfig {
+                verify_proof: cli.verify_proof_locally,
+            };
 
-            let proc = C2Processor::new(exec, prover_program, verifier_program, fs);
+            let backend = build_c2_backend(&cli, exec);
+            let proc = C2Processor::new(
+                backend,
+                prover_program,
+                verifier_program,
+                fs,
+                policy,
+                poll,
+                verify,
+                cli.fallback_to_local_c2,
+            );
             run_consumer_with_proc(proc)
         }
         Commands::Processor(ProcessorCommands::WindowPoST {
@@ -160,8 +498,31 @@ pub fn main() -> Result<()> {
         }) => {
             let fs: FileStorage = create_fs(&cli)?;
             let exec = create_gevulot_executor(&cli, fs.clone())?;
+            let policy = build_execution_policy(&cli.policy)?;
 
-            let proc = WindowPoStProcessor::new(exec, prover_program, verifier_program, fs);
+            let proc = WindowPoStProcessor::new(exec, prover_program, verifier_program, fs, policy);
+            run_consumer_with_proc(proc)
+        }
+        Commands::Processor(ProcessorCommands::WinningPoST {
+            prover_program,
+            verifier_program,
+        }) => {
+            let fs: FileStorage = create_fs(&cli)?;
+            let exec = create_gevulot_executor(&cli, fs.clone())?;
+            let policy = build_execution_policy(&cli.policy)?;
+
+            let proc = WinningPoStProcessor::new(exec, prover_program, verifier_program, fs, policy);
+            run_consumer_with_proc(proc)
+        }
+        Commands::Processor(ProcessorCommands::AggregateC2 {
+            prover_program,
+            verifier_program,
+        }) => {
+            let fs = create_fs(&cli)?;
+            let exec = create_gevulot_executor(&cli, fs.clone())?;
+            let policy = build_c2_execution_policy(&cli.policy)?;
+
+            let proc = AggregateC2Processor::new(exec, prover_program, verifier_program, fs, policy);
             run_consumer_with_proc(proc)
         }
         Commands::Fileserver { listen } => {
@@ -199,7 +560,22 @@ pub fn main() -> Result<()> {
         }) => {
             let fs = create_fs(&cli)?;
             let exec = create_gevulot_executor(&cli, fs.clone())?;
-            let proc = C2Processor::new(exec, prover_program, verifier_program, fs);
+            let policy = build_c2_execution_policy(&cli.policy)?;
+            let poll = build_poll_config(&cli.poll);
+            let verify = VerifyConfig {
+                verify_proof: cli.verify_proof_locally,
+            };
+            let backend = build_c2_backend(&cli, exec);
+            let proc = C2Processor::new(
+                backend,
+                prover_program,
+                verifier_program,
+                fs,
+                policy,
+                poll,
+                verify,
+                cli.fallback_to_local_c2,
+            );
             let f = File::open(&input_file).context("open the c2 input file")?;
             let c2_in: C2Input = decode_from(f).context("decode the c2 input data")?;
             let proof = proc.exec(c2_in)?;
@@ -214,8 +590,9 @@ pub fn main() -> Result<()> {
         }) => {
             let fs: FileStorage = create_fs(&cli)?;
             let exec = create_gevulot_executor(&cli, fs.clone())?;
+            let policy = build_execution_policy(&cli.policy)?;
 
-            let proc = WindowPoStProcessor::new(exec, prover_program, verifier_program, fs);
+            let proc = WindowPoStProcessor::new(exec, prover_program, verifier_program, fs, policy);
             let f = File::open(&input_file).context("open the c2 input file")?;
             let wdp2_in: WindowPoStPhase2Input =
                 decode_from(f).context("decode the c2 input data")?;
@@ -223,35 +600,259 @@ pub fn main() -> Result<()> {
             println!("{}", hex::encode(&proofs[0].1));
             Ok(())
         }
+
+        Commands::Exec(ExecCommands::WinningPoST {
+            prover_program,
+            verifier_program,
+            ref input_file,
+        }) => {
+            let fs: FileStorage = create_fs(&cli)?;
+            let exec = create_gevulot_executor(&cli, fs.clone())?;
+            let policy = build_execution_policy(&cli.policy)?;
+
+            let proc = WinningPoStProcessor::new(exec, prover_program, verifier_program, fs, policy);
+            let f = File::open(&input_file).context("open the winning post input file")?;
+            let wnp2_in: WinningPoStPhase2Input =
+                decode_from(f).context("decode the winning post input data")?;
+            let proofs = proc.exec_wnp2(wnp2_in)?;
+            println!("{}", hex::encode(&proofs[0].1));
+            Ok(())
+        }
+
+        Commands::Exec(ExecCommands::AggregateC2 {
+            prover_program,
+            verifier_program,
+            ref input_file,
+        }) => {
+            let fs = create_fs(&cli)?;
+            let exec = create_gevulot_executor(&cli, fs.clone())?;
+            let policy = build_c2_execution_policy(&cli.policy)?;
+
+            let proc = AggregateC2Processor::new(exec, prover_program, verifier_program, fs, policy);
+            let f = File::open(&input_file).context("open the aggregate c2 input file")?;
+            let c2_in: C2Input = decode_from(f).context("decode the aggregate c2 input data")?;
+            let proof = proc.exec(c2_in)?;
+            println!("{}", hex::encode(proof));
+            Ok(())
+        }
+
+        Commands::Server {
+            listen,
+            c2_prover_program,
+            c2_verifier_program,
+            window_post_prover_program,
+            window_post_verifier_program,
+        } => {
+            let fs = create_fs(&cli)?;
+            let exec = create_gevulot_executor(&cli, fs.clone())?;
+            // C2 is GPU-bound and defaults to requiring a GPU node; WindowPoSt is cheap
+            // enough to land anywhere, so the two must not share a single policy here.
+            let c2_policy = build_c2_execution_policy(&cli.policy)?;
+            let windowpost_policy = build_execution_policy(&cli.policy)?;
+            let poll = build_poll_config(&cli.poll);
+            let verify = VerifyConfig {
+                verify_proof: cli.verify_proof_locally,
+            };
+            let backend = build_c2_backend(&cli, exec.clone());
+            let c2 = C2Processor::new(
+                backend,
+                c2_prover_program,
+                c2_verifier_program,
+                fs.clone(),
+                c2_policy,
+                poll,
+                verify,
+                cli.fallback_to_local_c2,
+            );
+            let windowpost = WindowPoStProcessor::new(
+                exec,
+                window_post_prover_program,
+                window_post_verifier_program,
+                fs,
+                windowpost_policy,
+            );
+            let routes = server::routes(c2, windowpost);
+            info!("JSON-RPC control server listening on {listen}");
+            runtime.block_on(warp::serve(routes).run(listen));
+            Ok(())
+        }
+
+        Commands::Keygen {
+            ref out,
+            ref password_file,
+        } => {
+            let sk = SecretKey::from(libsecp256k1::SecretKey::random(&mut rand::thread_rng()));
+            let password = read_password(password_file.as_deref())?;
+            let json = sk.to_encrypted_json(&password).context("encrypt keystore")?;
+            fs::write(out, json).with_context(|| format!("write keystore: {}", out.display()))?;
+            info!("wrote encrypted keystore to {}", out.display());
+            Ok(())
+        }
+
+        Commands::Import {
+            ref keyfile,
+            ref out,
+            ref password_file,
+        } => {
+            let key_array =
+                fs::read(keyfile).with_context(|| format!("read key file: {}", keyfile.display()))?;
+            let sk = SecretKey::parse_slice(&key_array).context("parse secret key")?;
+            let password = read_password(password_file.as_deref())?;
+            let json = sk.to_encrypted_json(&password).context("encrypt keystore")?;
+            fs::write(out, json).with_context(|| format!("write keystore: {}", out.display()))?;
+            info!("wrote encrypted keystore to {}", out.display());
+            Ok(())
+        }
+    }
+}
+
+fn read_password(password_file: Option<&std::path::Path>) -> Result<String> {
+    match password_file {
+        Some(path) => Ok(fs::read_to_string(path)
+            .with_context(|| format!("read password file: {}", path.display()))?
+            .trim_end_matches(['\r', '\n'])
+            .to_string()),
+        None => rpassword::prompt_password("keystore password: ").context("read password"),
     }
 }
 
 fn create_fs(cli: &Cli) -> Result<FileStorage> {
-    let fs_path = cli.fileserver_path.display().to_string();
-    let fs_op = filestorage::init_operator(&filestorage::StorageParams::Fs { root: fs_path })
-        .context("init operator")?;
+    let params = match cli.storage.storage_backend {
+        StorageBackend::Fs => filestorage::StorageParams::Fs {
+            root: cli.fileserver_path.display().to_string(),
+        },
+        StorageBackend::S3 => filestorage::StorageParams::S3 {
+            endpoint_url: cli
+                .storage
+                .s3_endpoint_url
+                .clone()
+                .unwrap_or_else(|| filestorage::STORAGE_S3_DEFAULT_ENDPOINT.to_string()),
+            region: cli.storage.s3_region.clone(),
+            bucket: cli
+                .storage
+                .s3_bucket
+                .clone()
+                .context("--s3-bucket is required for the s3 storage backend")?,
+            access_key_id: cli.storage.s3_access_key_id.clone(),
+            secret_access_key: cli.storage.s3_secret_access_key.clone(),
+            security_token: cli.storage.s3_security_token.clone(),
+            root: cli.storage.s3_root.clone(),
+            disable_credential_loader: cli.storage.s3_disable_credential_loader,
+            enable_virtual_host_style: cli.storage.s3_enable_virtual_host_style,
+            role_arn: cli.storage.s3_role_arn.clone(),
+            external_id: cli.storage.s3_external_id.clone(),
+        },
+        StorageBackend::Http => filestorage::StorageParams::Http {
+            endpoint: cli
+                .storage
+                .http_endpoint
+                .clone()
+                .context("--http-endpoint is required for the http storage backend")?,
+            root: cli.storage.http_root.clone(),
+        },
+        StorageBackend::Memory => filestorage::StorageParams::Memory,
+        StorageBackend::Gcs => filestorage::StorageParams::Gcs {
+            bucket: cli
+                .storage
+                .gcs_bucket
+                .clone()
+                .context("--gcs-bucket is required for the gcs storage backend")?,
+            root: cli.storage.gcs_root.clone(),
+            credential: cli.storage.gcs_credential.clone(),
+            credential_path: cli.storage.gcs_credential_path.clone(),
+            endpoint: cli.storage.gcs_endpoint.clone(),
+        },
+        StorageBackend::Azblob => filestorage::StorageParams::Azblob {
+            container: cli
+                .storage
+                .azblob_container
+                .clone()
+                .context("--azblob-container is required for the azblob storage backend")?,
+            root: cli.storage.azblob_root.clone(),
+            endpoint: cli.storage.azblob_endpoint.clone(),
+            account_name: cli.storage.azblob_account_name.clone(),
+            account_key: cli.storage.azblob_account_key.clone(),
+        },
+        StorageBackend::WebDav => filestorage::StorageParams::WebDav {
+            endpoint: cli
+                .storage
+                .webdav_endpoint
+                .clone()
+                .context("--webdav-endpoint is required for the webdav storage backend")?,
+            root: cli.storage.webdav_root.clone(),
+            username: cli.storage.webdav_username.clone(),
+            password: cli.storage.webdav_password.clone(),
+        },
+    };
+    let fs_op = filestorage::init_operator(&params).context("init operator")?;
+    let envelope = load_envelope_config(&cli.envelope).context("load envelope config")?;
     Ok(FileStorage::new(
         fs_op.blocking(),
         cli.fileserver_base_url.clone(),
+        envelope,
     ))
 }
 
+/// Loads the optional sealed-storage [filestorage::EnvelopeConfig] from `--envelope-key-file`,
+/// accepting either a raw 32-byte key or a 64-character hex-encoded one.
+fn load_envelope_config(args: &EnvelopeArgs) -> Result<Option<filestorage::EnvelopeConfig>> {
+    let Some(path) = &args.envelope_key_file else {
+        return Ok(None);
+    };
+    let raw = fs::read(path).with_context(|| format!("read envelope key file: {}", path.display()))?;
+    let trimmed = std::str::from_utf8(&raw)
+        .ok()
+        .map(str::trim)
+        .and_then(|s| hex::decode(s).ok());
+    let key_vec = trimmed.unwrap_or(raw);
+    let key: [u8; 32] = key_vec
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("envelope key must be exactly 32 bytes (raw or hex-encoded)"))?;
+    Ok(Some(filestorage::EnvelopeConfig::new(
+        key,
+        args.envelope_compression_level,
+    )))
+}
+
 fn create_gevulot_executor(
     cli: &Cli,
     fs: FileStorage,
-) -> Result<Either<GevulotRpcExecutor, GevulotLocalExecutor>> {
+) -> Result<Either<Failover<GevulotRpcExecutor>, GevulotLocalExecutor>> {
     Ok(if cli.mock {
         Either::Right(GevulotLocalExecutor::new(fs))
     } else {
-        let rpc_client = Arc::new(RpcClient::new(&cli.rpc_url));
         let key_array = fs::read(&cli.keyfile)
             .with_context(|| format!("read key file: {}", cli.keyfile.display()))?;
-        let sk = Zeroizing::new(SecretKey::parse_slice(&key_array).context("parse secret key")?);
-        Either::Left(GevulotRpcExecutor::new(rpc_client, sk, fs))
+        let sk = load_secret_key(&key_array, cli.keyfile_password_file.as_deref())
+            .context("load signing key")?;
+
+        let nodes = std::iter::once(cli.rpc_url.as_str())
+            .chain(cli.rpc_failover_urls.iter().map(String::as_str))
+            .map(|rpc_url| {
+                let rpc_client = Arc::new(RpcClient::new(rpc_url));
+                GevulotRpcExecutor::new(rpc_client, sk.clone(), fs.clone())
+            })
+            .collect();
+
+        Either::Left(Failover::new(nodes, cli.rpc_selection_policy.into()))
     })
 }
 
-fn parse_hash(data: &str) -> Result<Hash> {
+/// A keyfile is treated as an encrypted keystore when it parses as JSON, and as a
+/// raw secp256k1 key otherwise, so existing plaintext `localkey.pki` files keep working.
+fn load_secret_key(key_array: &[u8], password_file: Option<&PathBuf>) -> Result<Zeroizing<SecretKey>> {
+    if key_array.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{') {
+        let password = read_password(password_file.map(PathBuf::as_path))
+            .context("keystore password required to decrypt keyfile")?;
+        SecretKey::from_encrypted_json(key_array, &password).context("decrypt keystore")
+    } else {
+        Ok(Zeroizing::new(
+            SecretKey::parse_slice(key_array).context("parse secret key")?,
+        ))
+    }
+}
+
+pub(crate) fn parse_hash(data: &str) -> Result<Hash> {
     Ok(Hash::new(
         hex::decode(data)
             .ok()
@@ -11,8 +11,10 @@ use zeroize::Zeroizing;
 
 use crate::filestorage::FileStorage;
 
+pub mod aggregate_c2;
 pub mod c2;
 pub mod windowpost;
+pub mod winningpost;
 
 #[derive(Clone)]
 pub struct Gevulot {
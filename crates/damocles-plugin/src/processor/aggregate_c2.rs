@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use filecoin_proofs_api::Commitment;
+use filecoin_proofs_api::ProverId;
+use filecoin_proofs_api::RegisteredAggregationProof;
+use filecoin_proofs_api::RegisteredSealProof;
+use filecoin_proofs_api::SectorId;
+use filecoin_proofs_api::Ticket;
+use gevulot_fil::codec::encode_into_with_digest;
+use gevulot_fil::AggregateSectorInput;
+use gevulot_fil::C2Input;
+use gevulot_node::types::transaction::ProgramData;
+use gevulot_node::types::transaction::WorkflowStep;
+use gevulot_node::types::Hash;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::time;
+use vc_processors::core::Processor;
+use vc_processors::core::Task as VTask;
+
+use super::c2::download_proof_checked;
+use crate::filestorage::FileStorage;
+use crate::gevulot::ExecutionPolicy;
+use crate::gevulot::GevulotExecutor;
+use crate::util::block_on;
+
+pub const STAGE_NAME_AGGREGATE_C2: &str = "aggregate_c2";
+
+/// Per-sector commit data handed to the aggregate C2 task; mirrors
+/// [AggregateSectorInput] but keeps the wire shape of [C2](super::c2::C2) consistent,
+/// i.e. the raw commit phase2 proof bytes rather than a re-decoded proof struct.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AggregateSectorCommit {
+    pub comm_r: Commitment,
+    pub comm_d: Commitment,
+    pub seed: Ticket,
+    pub sector_id: SectorId,
+    pub prover_id: ProverId,
+    pub proof: Vec<u8>,
+}
+
+/// Task of SnarkPack commit aggregation (`ProveCommitAggregate`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AggregateC2 {
+    pub registered_proof: RegisteredSealProof,
+    pub registered_aggregation: RegisteredAggregationProof,
+    pub sectors: Vec<AggregateSectorCommit>,
+}
+
+impl VTask for AggregateC2 {
+    const STAGE: &'static str = STAGE_NAME_AGGREGATE_C2;
+    type Output = Vec<u8>;
+}
+
+#[derive(Clone)]
+pub struct AggregateC2Processor<G> {
+    gevulot_executor: G,
+    prover_program: Hash,
+    verifier_program: Hash,
+    fs: FileStorage,
+    policy: ExecutionPolicy,
+}
+
+impl<G: GevulotExecutor> AggregateC2Processor<G> {
+    pub fn new(
+        gevulot_executor: G,
+        prover_program: Hash,
+        verifier_program: Hash,
+        fs: FileStorage,
+        policy: ExecutionPolicy,
+    ) -> Self {
+        Self {
+            gevulot_executor,
+            prover_program,
+            verifier_program,
+            fs,
+            policy,
+        }
+    }
+
+    pub fn exec(&self, c2_in: C2Input) -> Result<Vec<u8>> {
+        let mut c2_in_bytes = Vec::new();
+        let digest = encode_into_with_digest(&mut c2_in_bytes, &c2_in)
+            .context("encode the aggregate c2 input data")?;
+        let checksum_hash: Hash = (&digest).into();
+        let checksum = checksum_hash.to_string();
+        let vm_path = format!("/workspace/{checksum}");
+        self.fs
+            .write_chunked(&checksum, &c2_in_bytes)
+            .context("write aggregate c2 input data to filestorage")?;
+
+        let steps = vec![
+            WorkflowStep {
+                program: self.prover_program,
+                args: vec![
+                    String::from("--input"),
+                    vm_path.clone(),
+                    String::from("--proof-output"),
+                    String::from("proof.dat"),
+                ],
+                inputs: vec![ProgramData::Input {
+                    file_name: vm_path.clone(),
+                    file_url: self.fs.file_url(&checksum),
+                    checksum: checksum.clone(),
+                }],
+            },
+            WorkflowStep {
+                program: self.verifier_program,
+                args: vec![
+                    String::from("--input"),
+                    vm_path.clone(),
+                    String::from("--proof"),
+                    String::from("proof.dat"),
+                ],
+                inputs: vec![ProgramData::Output {
+                    source_program: self.prover_program,
+                    file_name: "proof.dat".to_string(),
+                }],
+            },
+        ];
+
+        block_on(async {
+            let hash = self
+                .gevulot_executor
+                .run_program(steps, self.policy.clone())
+                .await
+                .context("run program")?;
+            let mut interval = time::interval(Duration::from_secs(5));
+            time::timeout(Duration::from_mins(60), async {
+                loop {
+                    interval.tick().await;
+
+                    if self.gevulot_executor.query_proof(&hash).await?.is_some() {
+                        return download_proof_checked(&self.gevulot_executor, &hash).await;
+                    }
+                }
+            })
+            .await
+            .context("timed out")?
+        })
+    }
+}
+
+impl<G: GevulotExecutor + Send + Sync> Processor<AggregateC2> for AggregateC2Processor<G> {
+    fn name(&self) -> String {
+        "gevulot AggregateC2".to_string()
+    }
+
+    fn process(&self, task: AggregateC2) -> Result<<AggregateC2 as VTask>::Output> {
+        let sectors = task
+            .sectors
+            .into_iter()
+            .map(|sector| AggregateSectorInput {
+                comm_r: sector.comm_r,
+                comm_d: sector.comm_d,
+                seed: sector.seed,
+                sector_id: sector.sector_id,
+                prover_id: sector.prover_id,
+                c2out: filecoin_proofs_api::seal::SealCommitPhase2Output {
+                    proof: sector.proof,
+                },
+            })
+            .collect();
+
+        let c2_in = C2Input::AggregateV0 {
+            registered_proof: task.registered_proof,
+            registered_aggregation: task.registered_aggregation,
+            sectors,
+        };
+
+        self.exec(c2_in)
+    }
+}
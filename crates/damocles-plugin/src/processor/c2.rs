@@ -1,14 +1,16 @@
 use std::time::Duration;
+use std::time::Instant;
 
+use anyhow::anyhow;
 use anyhow::Context;
 use anyhow::Result;
-use base64::Engine;
+use filecoin_proofs_api::seal;
 use filecoin_proofs_api::seal::SealCommitPhase1Output;
 use filecoin_proofs_api::seal::SealCommitPhase2Output;
 use filecoin_proofs_api::ProverId;
 use filecoin_proofs_api::SectorId;
 use gevulot_fil::calc_checksum;
-use gevulot_fil::codec::encode;
+use gevulot_fil::codec::encode_into_with_digest;
 use gevulot_fil::C2Input;
 use gevulot_node::types::transaction::ProgramData;
 use gevulot_node::types::transaction::WorkflowStep;
@@ -16,15 +18,107 @@ use gevulot_node::types::Hash;
 use serde::Deserialize;
 use serde::Serialize;
 use tokio::time;
+use tracing::warn;
 use vc_processors::core::Processor;
 use vc_processors::core::Task as VTask;
 
 use crate::filestorage::FileStorage;
+use crate::gevulot::ExecutionPolicy;
 use crate::gevulot::GevulotExecutor;
+use crate::gevulot::TxStatus;
 use crate::util::block_on;
 
 pub const STAGE_NAME_C2: &str = "c2";
 
+/// Number of times a downloaded proof is re-fetched and re-checksummed before
+/// giving up on getting a consistent response from the Gevulot node.
+const PROOF_DOWNLOAD_RETRIES: u32 = 3;
+
+/// Governs the exponential-backoff poll loop [C2Processor::exec] runs while waiting for a
+/// submitted workflow's proof, and how many transient `run_program`/`query_proof` errors
+/// it tolerates before giving up. Long C2 proofs on a congested network routinely run
+/// past an hour, and a single dropped RPC response shouldn't throw away an in-flight proof.
+#[derive(Debug, Clone)]
+pub struct PollConfig {
+    /// Delay before the first poll, and the starting point for backoff.
+    pub initial_interval: Duration,
+    /// Upper bound the backed-off poll interval is capped at.
+    pub max_interval: Duration,
+    /// Multiplier applied to the poll interval after each unsuccessful poll.
+    pub backoff_factor: f64,
+    /// Total time budget for `run_program` plus every poll attempt.
+    pub overall_timeout: Duration,
+    /// How many consecutive transient `run_program`/`query_proof` errors are retried
+    /// (with the same backoff) before the task fails.
+    pub max_transient_retries: u32,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            initial_interval: Duration::from_secs(5),
+            max_interval: Duration::from_secs(60),
+            backoff_factor: 1.5,
+            overall_timeout: Duration::from_hours(2),
+            max_transient_retries: 5,
+        }
+    }
+}
+
+/// Gates additional validation [C2Processor::exec] performs on a downloaded proof before
+/// handing it back, so a faulty or malicious prover node can't poison the pipeline with
+/// an invalid proof that would otherwise only fail later on-chain.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VerifyConfig {
+    /// Locally re-run `verify_seal`/`verify_aggregate_seal_commit_proofs` on the
+    /// downloaded proof before returning it from `exec`.
+    pub verify_proof: bool,
+}
+
+/// Where [C2Processor::exec] obtains a c2 proof: dispatched to a Gevulot workflow, or
+/// computed in-process via `seal_commit_phase2`/`aggregate_seal_commit_proofs`. Unlike
+/// [crate::gevulot::local::GevulotLocalExecutor] (which still goes through the full
+/// workflow-submit-and-poll machinery, just against an in-memory node), [ExecBackend::Local]
+/// skips it entirely, so the crate can be exercised in tests and CI with no Gevulot node
+/// at all, mock or otherwise.
+#[derive(Clone)]
+pub enum ExecBackend<G> {
+    /// Dispatch the workflow to a Gevulot node via `G`.
+    Gevulot(G),
+    /// Compute the proof in this process.
+    Local,
+}
+
+/// A stage transition reported by [C2Processor::exec_with_progress] as it moves a c2 proof
+/// from submission to completion, so a caller building a miner dashboard or scheduler can
+/// show per-sector status and make preemption/priority decisions instead of staring at a
+/// 60-minute black box.
+#[derive(Debug, Clone)]
+pub struct C2Progress {
+    /// Hash of the submitted workflow. `None` before submission succeeds, and always when
+    /// running [ExecBackend::Local].
+    pub hash: Option<Hash>,
+    /// Time elapsed since `exec_with_progress` was called.
+    pub elapsed: Duration,
+    pub stage: C2Stage,
+}
+
+/// See [C2Progress].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum C2Stage {
+    /// The workflow has been submitted and is waiting for a worker to pick it up.
+    Queued,
+    /// At least one workflow step is executing, since `since` (elapsed into the call).
+    Executing { since: Duration },
+    /// The workflow succeeded; the proof is being downloaded and checksummed.
+    Downloading,
+    /// The proof (and, if [VerifyConfig::verify_proof] is set, its local re-verification)
+    /// completed successfully.
+    Completed,
+    /// [PollConfig::overall_timeout] elapsed before a proof arrived.
+    TimedOut,
+}
+
 /// Identifier for Actors.
 pub type ActorID = u64;
 
@@ -44,36 +138,163 @@ impl VTask for C2 {
 
 #[derive(Clone)]
 pub struct C2Processor<G> {
-    gevulot_executor: G,
+    backend: ExecBackend<G>,
     prover_program: Hash,
     verifier_program: Hash,
     fs: FileStorage,
+    policy: ExecutionPolicy,
+    poll: PollConfig,
+    verify: VerifyConfig,
+    /// Fall back to [ExecBackend::Local] when submitting the workflow to
+    /// [ExecBackend::Gevulot] keeps failing after [PollConfig::max_transient_retries].
+    /// Ignored when `backend` is already [ExecBackend::Local].
+    fallback_to_local: bool,
 }
 
 impl<G: GevulotExecutor> C2Processor<G> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        gevulot_executor: G,
+        backend: ExecBackend<G>,
         prover_program: Hash,
         verifier_program: Hash,
         fs: FileStorage,
+        policy: ExecutionPolicy,
+        poll: PollConfig,
+        verify: VerifyConfig,
+        fallback_to_local: bool,
     ) -> Self {
         Self {
-            gevulot_executor,
+            backend,
             prover_program,
             verifier_program,
             fs,
+            policy,
+            poll,
+            verify,
+            fallback_to_local,
         }
     }
 
     pub fn exec(&self, c2_in: C2Input) -> Result<Vec<u8>> {
-        let c2_in_bytes = encode(&c2_in).context("encode the c2 input data")?;
-        let checksum = calc_checksum(&c2_in_bytes).to_string();
+        let executor = match &self.backend {
+            ExecBackend::Local => return self.finish(&c2_in, exec_local(&c2_in)?),
+            ExecBackend::Gevulot(executor) => executor,
+        };
+
+        let steps = self.build_steps(&c2_in)?;
+
+        let hash = match block_on(self.submit(executor, steps)) {
+            Ok(hash) => hash,
+            Err(err) if self.fallback_to_local => {
+                warn!(
+                    "giving up on gevulot workflow submission, falling back to local c2 execution: {err:#}"
+                );
+                return self.finish(&c2_in, exec_local(&c2_in)?);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let proof = block_on(async {
+            time::timeout(self.poll.overall_timeout, self.poll_for_proof(executor, hash))
+                .await
+                .context("timed out waiting for c2 proof")?
+        })?;
+
+        self.finish(&c2_in, proof)
+    }
+
+    /// Like [Self::exec], but reports each [C2Stage] transition to `on_progress` as it
+    /// happens, so a caller can show live status instead of blocking silently until the
+    /// proof appears or the call times out.
+    pub fn exec_with_progress(
+        &self,
+        c2_in: C2Input,
+        mut on_progress: impl FnMut(C2Progress),
+    ) -> Result<Vec<u8>> {
+        let start = Instant::now();
+
+        let executor = match &self.backend {
+            ExecBackend::Local => {
+                let proof = exec_local(&c2_in)?;
+                on_progress(C2Progress {
+                    hash: None,
+                    elapsed: start.elapsed(),
+                    stage: C2Stage::Completed,
+                });
+                return self.finish(&c2_in, proof);
+            }
+            ExecBackend::Gevulot(executor) => executor,
+        };
+
+        let steps = self.build_steps(&c2_in)?;
+
+        let hash = match block_on(self.submit(executor, steps)) {
+            Ok(hash) => hash,
+            Err(err) if self.fallback_to_local => {
+                warn!(
+                    "giving up on gevulot workflow submission, falling back to local c2 execution: {err:#}"
+                );
+                let proof = exec_local(&c2_in)?;
+                on_progress(C2Progress {
+                    hash: None,
+                    elapsed: start.elapsed(),
+                    stage: C2Stage::Completed,
+                });
+                return self.finish(&c2_in, proof);
+            }
+            Err(err) => return Err(err),
+        };
+
+        on_progress(C2Progress {
+            hash: Some(hash),
+            elapsed: start.elapsed(),
+            stage: C2Stage::Queued,
+        });
+
+        let proof = block_on(async {
+            time::timeout(
+                self.poll.overall_timeout,
+                self.poll_for_proof_with_progress(executor, hash, start, &mut on_progress),
+            )
+            .await
+        });
+
+        let proof = match proof {
+            Ok(proof) => proof?,
+            Err(_) => {
+                on_progress(C2Progress {
+                    hash: Some(hash),
+                    elapsed: start.elapsed(),
+                    stage: C2Stage::TimedOut,
+                });
+                return Err(anyhow!("timed out waiting for c2 proof"));
+            }
+        };
+
+        let proof = self.finish(&c2_in, proof)?;
+        on_progress(C2Progress {
+            hash: Some(hash),
+            elapsed: start.elapsed(),
+            stage: C2Stage::Completed,
+        });
+
+        Ok(proof)
+    }
+
+    /// Builds the two-step `prove` + `verify` workflow for `c2_in`, writing the encoded
+    /// input to [FileStorage] so the workflow's first step can fetch it.
+    fn build_steps(&self, c2_in: &C2Input) -> Result<Vec<WorkflowStep>> {
+        let mut c2_in_bytes = Vec::new();
+        let digest =
+            encode_into_with_digest(&mut c2_in_bytes, c2_in).context("encode the c2 input data")?;
+        let checksum_hash: Hash = (&digest).into();
+        let checksum = checksum_hash.to_string();
         let vm_path = format!("/workspace/{checksum}");
         self.fs
-            .write(&checksum, c2_in_bytes)
+            .write_chunked(&checksum, &c2_in_bytes)
             .context("write c2 input data to filestorage")?;
 
-        let steps = vec![
+        Ok(vec![
             WorkflowStep {
                 program: self.prover_program,
                 args: vec![
@@ -101,30 +322,243 @@ impl<G: GevulotExecutor> C2Processor<G> {
                     file_name: "proof.dat".to_string(),
                 }],
             },
-        ];
+        ])
+    }
 
-        block_on(async {
-            let hash = self
-                .gevulot_executor
-                .run_program(steps)
-                .await
-                .context("run program")?;
-            let mut interval = time::interval(Duration::from_secs(5));
-            time::timeout(Duration::from_mins(60), async {
-                loop {
-                    interval.tick().await;
-
-                    if let Some(proof_string) = self.gevulot_executor.query_proof(&hash).await? {
-                        let proof =
-                            base64::engine::general_purpose::STANDARD.decode(proof_string)?;
-                        return Ok(proof);
-                    }
-                }
-            })
+    fn finish(&self, c2_in: &C2Input, proof: Vec<u8>) -> Result<Vec<u8>> {
+        if self.verify.verify_proof {
+            verify_proof_locally(c2_in, &proof).context("locally verify c2 proof")?;
+        }
+
+        Ok(proof)
+    }
+
+    /// Submits `steps` to `executor`, retrying transient errors with exponential backoff
+    /// per [PollConfig]. Kept separate from [Self::poll_for_proof] so a submission failure
+    /// can be told apart from a poll failure, which is what [Self::exec]'s
+    /// fallback-to-local logic needs.
+    async fn submit(&self, executor: &G, steps: Vec<WorkflowStep>) -> Result<Hash> {
+        self.retrying(|| executor.run_program(steps.clone(), self.policy.clone()))
             .await
-            .context("timed out")?
-        })
     }
+
+    /// Polls `executor` for the proof of the already-submitted workflow `hash`, retrying
+    /// transient errors with exponential backoff per [PollConfig]. Fails fast on
+    /// [TxStatus::Failed] instead of waiting out [PollConfig::overall_timeout].
+    async fn poll_for_proof(&self, executor: &G, hash: Hash) -> Result<Vec<u8>> {
+        let mut interval = self.poll.initial_interval;
+        loop {
+            time::sleep(interval).await;
+
+            match self.retrying(|| executor.query_status(&hash)).await? {
+                TxStatus::Succeeded => return download_proof_checked(executor, &hash).await,
+                TxStatus::Failed { reason } => {
+                    return Err(anyhow!("c2 workflow failed: {reason}"));
+                }
+                TxStatus::Pending | TxStatus::Running => {}
+            }
+
+            interval = backoff(interval, self.poll.backoff_factor, self.poll.max_interval);
+        }
+    }
+
+    /// Like [Self::poll_for_proof], but reports [C2Stage] transitions derived from
+    /// [GevulotExecutor::query_status] to `on_progress` as it polls.
+    async fn poll_for_proof_with_progress(
+        &self,
+        executor: &G,
+        hash: Hash,
+        start: Instant,
+        on_progress: &mut dyn FnMut(C2Progress),
+    ) -> Result<Vec<u8>> {
+        let mut interval = self.poll.initial_interval;
+        let mut executing_since = None;
+        loop {
+            time::sleep(interval).await;
+
+            let stage = match self.retrying(|| executor.query_status(&hash)).await? {
+                TxStatus::Pending => C2Stage::Queued,
+                TxStatus::Running => {
+                    let since = *executing_since.get_or_insert_with(|| start.elapsed());
+                    C2Stage::Executing { since }
+                }
+                TxStatus::Succeeded => {
+                    on_progress(C2Progress {
+                        hash: Some(hash),
+                        elapsed: start.elapsed(),
+                        stage: C2Stage::Downloading,
+                    });
+                    return download_proof_checked(executor, &hash).await;
+                }
+                TxStatus::Failed { reason } => {
+                    return Err(anyhow!("c2 workflow failed: {reason}"));
+                }
+            };
+
+            on_progress(C2Progress {
+                hash: Some(hash),
+                elapsed: start.elapsed(),
+                stage,
+            });
+
+            interval = backoff(interval, self.poll.backoff_factor, self.poll.max_interval);
+        }
+    }
+
+    /// Runs `attempt` once, and again with backoff on each error, up to
+    /// `max_transient_retries` times before giving up.
+    async fn retrying<T, F, Fut>(&self, attempt: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut interval = self.poll.initial_interval;
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(err) if retries < self.poll.max_transient_retries => {
+                    retries += 1;
+                    warn!(retries, "transient error in c2 workflow, retrying: {err:#}");
+                    time::sleep(interval).await;
+                    interval = backoff(interval, self.poll.backoff_factor, self.poll.max_interval);
+                }
+                Err(err) => {
+                    return Err(err.context(format!(
+                        "gave up after {} transient retries",
+                        self.poll.max_transient_retries
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Multiplies `interval` by `factor`, capped at `max`.
+fn backoff(interval: Duration, factor: f64, max: Duration) -> Duration {
+    interval.mul_f64(factor).min(max)
+}
+
+/// Computes the c2 proof in-process, reusing the exact logic the prover binary runs inside
+/// its Gevulot VM, so [ExecBackend::Local] needs no workflow dispatch or network access.
+fn exec_local(c2_in: &C2Input) -> Result<Vec<u8>> {
+    match c2_in {
+        C2Input::V0 {
+            c1out,
+            prover_id,
+            sector_id,
+        } => {
+            let c2out = seal::seal_commit_phase2(c1out.clone(), *prover_id, *sector_id)
+                .context("run seal_commit_phase2")?;
+            Ok(c2out.proof)
+        }
+        C2Input::AggregateV0 {
+            registered_proof,
+            registered_aggregation,
+            sectors,
+        } => {
+            let comm_rs: Vec<_> = sectors.iter().map(|s| s.comm_r).collect();
+            let seeds: Vec<_> = sectors.iter().map(|s| s.seed).collect();
+            let commit_outputs: Vec<_> = sectors
+                .iter()
+                .map(|s| SealCommitPhase2Output {
+                    proof: s.c2out.proof.clone(),
+                })
+                .collect();
+
+            seal::aggregate_seal_commit_proofs(
+                *registered_proof,
+                *registered_aggregation,
+                &comm_rs,
+                &seeds,
+                &commit_outputs,
+            )
+            .context("run aggregate_seal_commit_proofs")
+        }
+    }
+}
+
+/// Locally re-runs the appropriate verifier over `proof` against the known public inputs
+/// in `c2_in`, rejecting a proof a faulty or malicious prover node claims succeeded.
+fn verify_proof_locally(c2_in: &C2Input, proof: &[u8]) -> Result<()> {
+    match c2_in {
+        C2Input::V0 {
+            c1out,
+            prover_id,
+            sector_id,
+        } => {
+            let valid = seal::verify_seal(
+                c1out.registered_proof,
+                c1out.comm_r,
+                c1out.comm_d,
+                *prover_id,
+                *sector_id,
+                c1out.ticket,
+                c1out.seed,
+                proof,
+            )?;
+            anyhow::ensure!(valid, "verify_seal rejected the downloaded proof");
+        }
+        C2Input::AggregateV0 {
+            registered_proof,
+            registered_aggregation,
+            sectors,
+        } => {
+            let comm_rs: Vec<_> = sectors.iter().map(|s| s.comm_r).collect();
+            let comm_ds: Vec<_> = sectors.iter().map(|s| s.comm_d).collect();
+            let seeds: Vec<_> = sectors.iter().map(|s| s.seed).collect();
+            let commit_outputs: Vec<_> = sectors
+                .iter()
+                .map(|s| SealCommitPhase2Output {
+                    proof: s.c2out.proof.clone(),
+                })
+                .collect();
+
+            let valid = seal::verify_aggregate_seal_commit_proofs(
+                *registered_proof,
+                *registered_aggregation,
+                proof.to_vec(),
+                &comm_rs,
+                &comm_ds,
+                &seeds,
+                commit_outputs,
+            )?;
+            anyhow::ensure!(
+                valid,
+                "verify_aggregate_seal_commit_proofs rejected the downloaded proof"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Downloads the proof for `hash` and re-fetches it once to verify the blake3
+/// checksum matches, retrying up to [PROOF_DOWNLOAD_RETRIES] times so a proof
+/// corrupted in transit between the node and this client turns into a clean,
+/// retried failure instead of being silently handed back to the caller.
+pub(crate) async fn download_proof_checked<G: GevulotExecutor>(exec: &G, hash: &Hash) -> Result<Vec<u8>> {
+    for attempt in 0..=PROOF_DOWNLOAD_RETRIES {
+        let proof = exec
+            .query_proof(hash)
+            .await?
+            .context("proof disappeared while verifying")?;
+        let checksum = calc_checksum(&proof);
+
+        let Some(confirm) = exec.query_proof(hash).await? else {
+            // The node no longer reports a proof for this workflow; trust what we have.
+            return Ok(proof);
+        };
+        if calc_checksum(&confirm) == checksum {
+            return Ok(proof);
+        }
+
+        warn!(attempt, %hash, "downloaded proof failed checksum re-verification, retrying");
+    }
+
+    Err(anyhow!(
+        "proof for {hash} failed checksum verification after {PROOF_DOWNLOAD_RETRIES} retries"
+    ))
 }
 
 impl<G: GevulotExecutor + Send + Sync> Processor<C2> for C2Processor<G> {
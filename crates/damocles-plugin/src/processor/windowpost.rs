@@ -2,9 +2,9 @@ use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use anyhow::anyhow;
 use anyhow::Context;
 use anyhow::Result;
-use base64::Engine;
 use filecoin_proofs_api::ChallengeSeed;
 use filecoin_proofs_api::Commitment;
 use filecoin_proofs_api::ProverId;
@@ -13,8 +13,8 @@ use filecoin_proofs_api::SectorId;
 use filecoin_proofs_api::SnarkProof;
 use filecoin_proofs_api::StorageProofsError;
 use forest_address::Address;
-use gevulot_fil::calc_checksum;
-use gevulot_fil::codec::encode;
+use gevulot_fil::codec::decode;
+use gevulot_fil::codec::encode_into_with_digest;
 use gevulot_fil::WindowPoStPhase2Input;
 use gevulot_node::types::transaction::ProgramData;
 use gevulot_node::types::transaction::WorkflowStep;
@@ -26,9 +26,12 @@ use vc_processors::core::Processor;
 use vc_processors::core::Task as VTask;
 use windowpost_api::types::PrivateReplicaInfo;
 
+use super::c2::download_proof_checked;
 use super::c2::ActorID;
 use crate::filestorage::FileStorage;
+use crate::gevulot::ExecutionPolicy;
 use crate::gevulot::GevulotExecutor;
+use crate::gevulot::TxStatus;
 use crate::util::block_on;
 
 pub const STAGE_NAME_WINDOW_POST: &str = "windowpost";
@@ -67,6 +70,7 @@ pub struct WindowPoStProcessor<G> {
     prover_program: Hash,
     verifier_program: Hash,
     fs: FileStorage,
+    policy: ExecutionPolicy,
 }
 
 impl<G: GevulotExecutor> WindowPoStProcessor<G> {
@@ -75,12 +79,14 @@ impl<G: GevulotExecutor> WindowPoStProcessor<G> {
         prover_program: Hash,
         verifier_program: Hash,
         fs: FileStorage,
+        policy: ExecutionPolicy,
     ) -> Self {
         Self {
             gevulot_executor,
             prover_program,
             verifier_program,
             fs,
+            policy,
         }
     }
 
@@ -88,12 +94,14 @@ impl<G: GevulotExecutor> WindowPoStProcessor<G> {
         &self,
         wdp2_in: WindowPoStPhase2Input,
     ) -> Result<Vec<(RegisteredPoStProof, SnarkProof)>> {
-        let wd_phase2_in_bytes =
-            encode(&wdp2_in).context("encode the window post phase2 input data")?;
-        let checksum = calc_checksum(&wd_phase2_in_bytes).to_string();
+        let mut wd_phase2_in_bytes = Vec::new();
+        let digest = encode_into_with_digest(&mut wd_phase2_in_bytes, &wdp2_in)
+            .context("encode the window post phase2 input data")?;
+        let checksum_hash: Hash = (&digest).into();
+        let checksum = checksum_hash.to_string();
 
         self.fs
-            .write(&checksum, wd_phase2_in_bytes)
+            .write_chunked(&checksum, &wd_phase2_in_bytes)
             .context("write window post phase2 input data to filestorage")?;
 
         let steps = vec![
@@ -129,7 +137,7 @@ impl<G: GevulotExecutor> WindowPoStProcessor<G> {
         block_on(async {
             let hash = self
                 .gevulot_executor
-                .run_program(steps)
+                .run_program(steps, self.policy.clone())
                 .await
                 .context("run program")?;
             let mut interval = time::interval(Duration::from_secs(5));
@@ -137,10 +145,17 @@ impl<G: GevulotExecutor> WindowPoStProcessor<G> {
                 loop {
                     interval.tick().await;
 
-                    if let Some(proof_string) = self.gevulot_executor.query_proof(&hash).await? {
-                        let _proof =
-                            base64::engine::general_purpose::STANDARD.decode(proof_string)?;
-                        return Ok(vec![]);
+                    match self.gevulot_executor.query_status(&hash).await? {
+                        TxStatus::Succeeded => {
+                            let proof_bytes =
+                                download_proof_checked(&self.gevulot_executor, &hash).await?;
+                            return decode(&proof_bytes)
+                                .context("decode the window post phase2 proof output");
+                        }
+                        TxStatus::Failed { reason } => {
+                            return Err(anyhow!("window post phase2 workflow failed: {reason}"));
+                        }
+                        TxStatus::Pending | TxStatus::Running => {}
                     }
                 }
             })
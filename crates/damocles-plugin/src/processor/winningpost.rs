@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use anyhow::Context;
+use anyhow::Result;
+use filecoin_proofs_api::ChallengeSeed;
+use filecoin_proofs_api::Commitment;
+use filecoin_proofs_api::ProverId;
+use filecoin_proofs_api::RegisteredPoStProof;
+use filecoin_proofs_api::SectorId;
+use filecoin_proofs_api::SnarkProof;
+use filecoin_proofs_api::StorageProofsError;
+use forest_address::Address;
+use gevulot_fil::codec::decode;
+use gevulot_fil::codec::encode_into_with_digest;
+use gevulot_fil::WinningPoStPhase2Input;
+use gevulot_node::types::transaction::ProgramData;
+use gevulot_node::types::transaction::WorkflowStep;
+use gevulot_node::types::Hash;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::time;
+use vc_processors::core::Processor;
+use vc_processors::core::Task as VTask;
+use windowpost_api::types::PrivateReplicaInfo;
+
+use super::c2::download_proof_checked;
+use super::c2::ActorID;
+use crate::filestorage::FileStorage;
+use crate::gevulot::ExecutionPolicy;
+use crate::gevulot::GevulotExecutor;
+use crate::gevulot::TxStatus;
+use crate::util::block_on;
+
+pub const STAGE_NAME_WINNING_POST: &str = "winningpost";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PoStReplicaInfo {
+    pub sector_id: SectorId,
+    pub comm_r: Commitment,
+    pub cache_dir: PathBuf,
+    pub sealed_file: PathBuf,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WinningPoStOutput {
+    pub proofs: Vec<SnarkProof>,
+    pub faults: Vec<u64>,
+}
+
+/// Task of WinningPoSt. Unlike [crate::processor::windowpost::WindowPoSt], this is on the
+/// block-production hot path: `replicas` is only the handful of sectors the challenge
+/// randomness selected, not the miner's whole sector set.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WinningPoSt {
+    pub miner_id: ActorID,
+    pub proof_type: RegisteredPoStProof,
+    pub replicas: Vec<PoStReplicaInfo>,
+    pub seed: ChallengeSeed,
+}
+
+impl VTask for WinningPoSt {
+    const STAGE: &'static str = STAGE_NAME_WINNING_POST;
+    type Output = WinningPoStOutput;
+}
+
+#[derive(Clone)]
+pub struct WinningPoStProcessor<G> {
+    gevulot_executor: G,
+    prover_program: Hash,
+    verifier_program: Hash,
+    fs: FileStorage,
+    policy: ExecutionPolicy,
+}
+
+impl<G: GevulotExecutor> WinningPoStProcessor<G> {
+    pub fn new(
+        gevulot_executor: G,
+        prover_program: Hash,
+        verifier_program: Hash,
+        fs: FileStorage,
+        policy: ExecutionPolicy,
+    ) -> Self {
+        Self {
+            gevulot_executor,
+            prover_program,
+            verifier_program,
+            fs,
+            policy,
+        }
+    }
+
+    pub fn exec_wnp2(
+        &self,
+        wnp2_in: WinningPoStPhase2Input,
+    ) -> Result<Vec<(RegisteredPoStProof, SnarkProof)>> {
+        let mut wn_phase2_in_bytes = Vec::new();
+        let digest = encode_into_with_digest(&mut wn_phase2_in_bytes, &wnp2_in)
+            .context("encode the winning post phase2 input data")?;
+        let checksum_hash: Hash = (&digest).into();
+        let checksum = checksum_hash.to_string();
+
+        self.fs
+            .write_chunked(&checksum, &wn_phase2_in_bytes)
+            .context("write winning post phase2 input data to filestorage")?;
+
+        let steps = vec![
+            WorkflowStep {
+                program: self.prover_program,
+                args: vec![
+                    String::from("--input"),
+                    checksum.clone(),
+                    String::from("--proof-output"),
+                    String::from("proof.dat"),
+                ],
+                inputs: vec![ProgramData::Input {
+                    file_name: checksum.clone(),
+                    file_url: self.fs.file_url(&checksum),
+                    checksum: checksum.clone(),
+                }],
+            },
+            WorkflowStep {
+                program: self.verifier_program,
+                args: vec![
+                    String::from("--input"),
+                    checksum.clone(),
+                    String::from("--proof"),
+                    String::from("proof.dat"),
+                ],
+                inputs: vec![ProgramData::Output {
+                    source_program: self.prover_program,
+                    file_name: "proof.dat".to_string(),
+                }],
+            },
+        ];
+
+        block_on(async {
+            let hash = self
+                .gevulot_executor
+                .run_program(steps, self.policy.clone())
+                .await
+                .context("run program")?;
+            let mut interval = time::interval(Duration::from_secs(5));
+            time::timeout(Duration::from_mins(60), async {
+                loop {
+                    interval.tick().await;
+
+                    match self.gevulot_executor.query_status(&hash).await? {
+                        TxStatus::Succeeded => {
+                            let proof_bytes =
+                                download_proof_checked(&self.gevulot_executor, &hash).await?;
+                            return decode(&proof_bytes)
+                                .context("decode the winning post phase2 proof output");
+                        }
+                        TxStatus::Failed { reason } => {
+                            return Err(anyhow!("winning post phase2 workflow failed: {reason}"));
+                        }
+                        TxStatus::Pending | TxStatus::Running => {}
+                    }
+                }
+            })
+            .await
+            .context("timed out")?
+        })
+    }
+}
+
+impl<G: GevulotExecutor + Send + Sync> Processor<WinningPoSt> for WinningPoStProcessor<G> {
+    fn name(&self) -> String {
+        "gevulot WinningPoSt".to_string()
+    }
+
+    fn process(&self, task: WinningPoSt) -> Result<<WinningPoSt as VTask>::Output> {
+        let replicas = BTreeMap::from_iter(task.replicas.into_iter().map(|rep| {
+            (
+                rep.sector_id,
+                PrivateReplicaInfo::new(
+                    task.proof_type,
+                    rep.comm_r,
+                    rep.cache_dir,
+                    rep.sealed_file,
+                ),
+            )
+        }));
+
+        let prover_id = to_prover_id(task.miner_id);
+        match windowpost_api::generate_winning_post_vanilla_proofs(&task.seed, &replicas, prover_id)
+        {
+            Ok((pub_sectors, vanilla_proofs)) => {
+                let wnp2_in = WinningPoStPhase2Input::V0 {
+                    proof_type: task.proof_type,
+                    randomness: task.seed,
+                    prover_id,
+                    pub_sectors,
+                    vanilla_proofs,
+                };
+                let proofs = self.exec_wnp2(wnp2_in)?;
+                Ok(WinningPoStOutput {
+                    proofs: proofs.into_iter().map(|x| x.1).collect(),
+                    faults: vec![],
+                })
+            }
+            Err(e) => match e.downcast_ref::<StorageProofsError>() {
+                Some(StorageProofsError::FaultySectors(sectors)) => Ok(WinningPoStOutput {
+                    proofs: vec![],
+                    faults: sectors.iter().map(|id| (*id).into()).collect(),
+                }),
+                _ => Err(e),
+            },
+        }
+    }
+}
+
+fn to_prover_id(miner_id: ActorID) -> ProverId {
+    let mut prover_id: ProverId = Default::default();
+    let actor_addr_payload = Address::new_id(miner_id).payload_bytes();
+    prover_id[..actor_addr_payload.len()].copy_from_slice(actor_addr_payload.as_ref());
+    prover_id
+}
@@ -0,0 +1,216 @@
+//! HTTP JSON-RPC control/status server for proving tasks.
+//!
+//! The processors normally only run as stdio `vc-processors` consumers, which
+//! requires a long-lived connection per task and gives external schedulers no
+//! way to enqueue work programmatically. This mode exposes the same `C2` and
+//! `WindowPoSt` proving pipelines over a small JSON-RPC 2.0 interface
+//! (`submit_c2`, `submit_window_post`, `get_task_status`, `get_proof`) so a
+//! scheduler can submit a task and poll for its result asynchronously. Task
+//! results are cached in-process keyed by a locally generated [Hash], the
+//! same pattern [crate::gevulot::local::GevulotLocalExecutor] uses for its
+//! proof cache, so repeated polls don't re-run any work.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Context;
+use anyhow::Result;
+use gevulot_node::types::Hash;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tokio::task::spawn_blocking;
+use vc_processors::core::Processor;
+use warp::Filter;
+
+use crate::gevulot::GevulotExecutor;
+use crate::parse_hash;
+use crate::processor::c2::C2Processor;
+use crate::processor::c2::C2;
+use crate::processor::windowpost::WindowPoSt;
+use crate::processor::windowpost::WindowPoStProcessor;
+
+/// Status of a task submitted through the control server.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TaskState {
+    Pending,
+    Running,
+    Done { proof: String },
+    Failed { reason: String },
+}
+
+#[derive(Clone, Default)]
+struct ResultsCache {
+    inner: Arc<Mutex<HashMap<Hash, TaskState>>>,
+}
+
+impl ResultsCache {
+    async fn set(&self, hash: Hash, state: TaskState) {
+        self.inner.lock().await.insert(hash, state);
+    }
+
+    async fn get(&self, hash: &Hash) -> Option<TaskState> {
+        self.inner.lock().await.get(hash).cloned()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    method: String,
+    #[serde(default)]
+    params: Value,
+    id: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+/// Builds the warp filter serving the control/status JSON-RPC API at `POST /`.
+pub fn routes<G>(
+    c2: C2Processor<G>,
+    windowpost: WindowPoStProcessor<G>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where G: GevulotExecutor + Clone + Send + Sync + 'static {
+    let cache = ResultsCache::default();
+
+    warp::post()
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and_then(move |req: RpcRequest| {
+            let cache = cache.clone();
+            let c2 = c2.clone();
+            let windowpost = windowpost.clone();
+            async move {
+                let resp = dispatch(cache, c2, windowpost, req).await;
+                Ok::<_, warp::Rejection>(warp::reply::json(&resp))
+            }
+        })
+}
+
+async fn dispatch<G>(
+    cache: ResultsCache,
+    c2: C2Processor<G>,
+    windowpost: WindowPoStProcessor<G>,
+    req: RpcRequest,
+) -> RpcResponse
+where G: GevulotExecutor + Clone + Send + Sync + 'static {
+    let id = req.id.clone();
+    let result = match req.method.as_str() {
+        "submit_c2" => submit_c2(cache, c2, req.params).await,
+        "submit_window_post" => submit_window_post(cache, windowpost, req.params).await,
+        "get_task_status" => get_task_status(cache, req.params).await,
+        "get_proof" => get_proof(cache, req.params).await,
+        other => Err(anyhow::anyhow!("unknown method: {other}")),
+    };
+
+    match result {
+        Ok(result) => RpcResponse {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(err) => RpcResponse {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(RpcError {
+                code: -32000,
+                message: err.to_string(),
+            }),
+        },
+    }
+}
+
+async fn submit_c2<G>(cache: ResultsCache, c2: C2Processor<G>, params: Value) -> Result<Value>
+where G: GevulotExecutor + Clone + Send + Sync + 'static {
+    let task: C2 = serde_json::from_value(params).context("parse c2 task")?;
+    let hash = Hash::random(&mut rand::thread_rng());
+    cache.set(hash, TaskState::Pending).await;
+
+    tokio::spawn(async move {
+        cache.set(hash, TaskState::Running).await;
+        let state = match spawn_blocking(move || c2.process(task)).await {
+            Ok(Ok(output)) => TaskState::Done {
+                proof: hex::encode(output.proof),
+            },
+            Ok(Err(err)) => TaskState::Failed {
+                reason: err.to_string(),
+            },
+            Err(join_err) => TaskState::Failed {
+                reason: join_err.to_string(),
+            },
+        };
+        cache.set(hash, state).await;
+    });
+
+    Ok(serde_json::json!({ "hash": hash.to_string() }))
+}
+
+async fn submit_window_post<G>(
+    cache: ResultsCache,
+    windowpost: WindowPoStProcessor<G>,
+    params: Value,
+) -> Result<Value>
+where G: GevulotExecutor + Clone + Send + Sync + 'static {
+    let task: WindowPoSt = serde_json::from_value(params).context("parse window_post task")?;
+    let hash = Hash::random(&mut rand::thread_rng());
+    cache.set(hash, TaskState::Pending).await;
+
+    tokio::spawn(async move {
+        cache.set(hash, TaskState::Running).await;
+        let state = match spawn_blocking(move || windowpost.process(task)).await {
+            Ok(Ok(output)) => TaskState::Done {
+                proof: output.proofs.iter().map(hex::encode).collect::<Vec<_>>().join(","),
+            },
+            Ok(Err(err)) => TaskState::Failed {
+                reason: err.to_string(),
+            },
+            Err(join_err) => TaskState::Failed {
+                reason: join_err.to_string(),
+            },
+        };
+        cache.set(hash, state).await;
+    });
+
+    Ok(serde_json::json!({ "hash": hash.to_string() }))
+}
+
+async fn get_task_status(cache: ResultsCache, params: Value) -> Result<Value> {
+    let hash = parse_task_hash(params)?;
+    let state = cache.get(&hash).await.context("unknown task hash")?;
+    serde_json::to_value(state).context("serialize task state")
+}
+
+async fn get_proof(cache: ResultsCache, params: Value) -> Result<Value> {
+    let hash = parse_task_hash(params)?;
+    match cache.get(&hash).await.context("unknown task hash")? {
+        TaskState::Done { proof } => Ok(serde_json::json!({ "proof": proof })),
+        other => Ok(serde_json::json!({ "proof": Value::Null, "status": other })),
+    }
+}
+
+#[derive(Deserialize)]
+struct TaskHashParams {
+    hash: String,
+}
+
+fn parse_task_hash(params: Value) -> Result<Hash> {
+    let params: TaskHashParams = serde_json::from_value(params).context("parse task hash params")?;
+    parse_hash(&params.hash)
+}
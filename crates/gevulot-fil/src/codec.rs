@@ -55,6 +55,93 @@ where
     bincode::deserialize_from(zstd_decoder).map_err(Into::into)
 }
 
+/// A [std::io::Write] wrapper that feeds every byte written through it into a
+/// running blake3 hash, so callers can obtain the digest of a stream without a
+/// second pass over the data.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: blake3::Hasher,
+}
+
+impl<W: std::io::Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+        }
+    }
+
+    fn finalize(&self) -> blake3::Hash {
+        self.hasher.finalize()
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A [std::io::Read] wrapper that feeds every byte read through it into a
+/// running blake3 hash, so callers can obtain the digest of a stream without a
+/// second pass over the data.
+struct HashingReader<R> {
+    inner: R,
+    hasher: blake3::Hasher,
+}
+
+impl<R: std::io::Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+        }
+    }
+
+    fn finalize(&self) -> blake3::Hash {
+        self.hasher.finalize()
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Like [encode_into], but also returns the blake3 digest of the encoded (compressed)
+/// bytes, computed in-flight so large blobs only need a single pass over the data.
+pub fn encode_into_with_digest<W, T>(writer: W, value: &T) -> Result<blake3::Hash>
+where
+    W: std::io::Write,
+    T: serde::Serialize + ?Sized,
+{
+    let mut hashing = HashingWriter::new(writer);
+    encode_into(&mut hashing, value)?;
+    Ok(hashing.finalize())
+}
+
+/// Like [decode_from], but also returns the blake3 digest of the encoded (compressed)
+/// bytes read from `reader`, computed in-flight so large blobs only need a single pass.
+pub fn decode_from_with_digest<R, T>(reader: R) -> Result<(T, blake3::Hash)>
+where
+    R: std::io::Read,
+    T: serde::de::DeserializeOwned + ?Sized,
+{
+    let mut hashing = HashingReader::new(reader);
+    let value = decode_from(&mut hashing)?;
+    let digest = hashing.finalize();
+    Ok((value, digest))
+}
+
 #[cfg(test)]
 mod tests {
     use serde::Deserialize;
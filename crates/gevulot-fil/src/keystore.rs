@@ -0,0 +1,170 @@
+//! Web3 Secret Storage compatible encrypted keystore for [SecretKey].
+//!
+//! The on-disk format mirrors the scheme used by `go-ethereum`/`eth-keystore`:
+//! the private key is encrypted with AES-128-CTR under a key derived from the
+//! user's password via scrypt, and a MAC over `keccak256(derived[16..32] ||
+//! ciphertext)` is stored alongside it so a wrong password or a tampered file
+//! is detected before the key material is ever used.
+
+use aes::cipher::KeyIvInit;
+use aes::cipher::StreamCipher;
+use anyhow::ensure;
+use anyhow::Context;
+use anyhow::Result;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::Deserialize;
+use serde::Serialize;
+use sha3::Digest;
+use sha3::Keccak256;
+use zeroize::Zeroize;
+use zeroize::Zeroizing;
+
+use crate::SecretKey;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+const KEYSTORE_VERSION: u32 = 3;
+const CIPHER: &str = "aes-128-ctr";
+const KDF: &str = "scrypt";
+
+/// Default scrypt cost parameters, matching the Web3 Secret Storage reference
+/// implementation's "light" params.
+const DEFAULT_SCRYPT_LOG_N: u8 = 13;
+const DEFAULT_SCRYPT_R: u32 = 8;
+const DEFAULT_SCRYPT_P: u32 = 1;
+const SCRYPT_DKLEN: usize = 32;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeystoreJson {
+    version: u32,
+    crypto: CryptoJson,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CryptoJson {
+    cipher: String,
+    cipherparams: CipherParamsJson,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: ScryptParamsJson,
+    mac: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CipherParamsJson {
+    iv: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ScryptParamsJson {
+    dklen: usize,
+    n: u32,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+impl SecretKey {
+    /// Encrypts this key using the Web3 Secret Storage scheme, returning the
+    /// serialized keystore JSON.
+    pub fn to_encrypted_json(&self, password: &str) -> Result<Vec<u8>> {
+        let mut salt = [0u8; 32];
+        OsRng.fill_bytes(&mut salt);
+        let mut iv = [0u8; 16];
+        OsRng.fill_bytes(&mut iv);
+
+        let derived = derive_key(password, &salt, DEFAULT_SCRYPT_LOG_N, DEFAULT_SCRYPT_R, DEFAULT_SCRYPT_P)?;
+
+        let mut ciphertext = self.inner().serialize().to_vec();
+        Aes128Ctr::new((&derived[0..16]).into(), (&iv).into()).apply_keystream(&mut ciphertext);
+
+        let mac = compute_mac(&derived[16..32], &ciphertext);
+
+        let json = KeystoreJson {
+            version: KEYSTORE_VERSION,
+            crypto: CryptoJson {
+                cipher: CIPHER.to_string(),
+                cipherparams: CipherParamsJson { iv: hex::encode(iv) },
+                ciphertext: hex::encode(&ciphertext),
+                kdf: KDF.to_string(),
+                kdfparams: ScryptParamsJson {
+                    dklen: SCRYPT_DKLEN,
+                    n: 1u32 << DEFAULT_SCRYPT_LOG_N,
+                    r: DEFAULT_SCRYPT_R,
+                    p: DEFAULT_SCRYPT_P,
+                    salt: hex::encode(salt),
+                },
+                mac: hex::encode(mac),
+            },
+        };
+
+        ciphertext.zeroize();
+        serde_json::to_vec_pretty(&json).context("serialize keystore json")
+    }
+
+    /// Decrypts a keystore produced by [SecretKey::to_encrypted_json], verifying the MAC
+    /// before attempting decryption so a wrong password or corrupted file is reported
+    /// cleanly instead of yielding garbage key material.
+    pub fn from_encrypted_json(bytes: &[u8], password: &str) -> Result<Zeroizing<Self>> {
+        let json: KeystoreJson = serde_json::from_slice(bytes).context("parse keystore json")?;
+        ensure!(json.crypto.cipher == CIPHER, "unsupported cipher: {}", json.crypto.cipher);
+        ensure!(json.crypto.kdf == KDF, "unsupported kdf: {}", json.crypto.kdf);
+
+        let kdfparams = &json.crypto.kdfparams;
+        let salt = hex::decode(&kdfparams.salt).context("decode salt")?;
+        let log_n = (kdfparams.n as f64).log2().round() as u8;
+        let derived = derive_key(password, &salt, log_n, kdfparams.r, kdfparams.p)?;
+
+        let mut ciphertext = hex::decode(&json.crypto.ciphertext).context("decode ciphertext")?;
+        let expected_mac = hex::decode(&json.crypto.mac).context("decode mac")?;
+        let mac = compute_mac(&derived[16..32], &ciphertext);
+        ensure!(
+            mac == expected_mac.as_slice(),
+            "invalid password or corrupted keystore (mac mismatch)"
+        );
+
+        let iv = hex::decode(&json.crypto.cipherparams.iv).context("decode iv")?;
+        Aes128Ctr::new((&derived[0..16]).into(), iv.as_slice().into()).apply_keystream(&mut ciphertext);
+
+        let sk = SecretKey::parse_slice(&ciphertext);
+        ciphertext.zeroize();
+        Ok(Zeroizing::new(sk?))
+    }
+}
+
+fn derive_key(password: &str, salt: &[u8], log_n: u8, r: u32, p: u32) -> Result<[u8; 32]> {
+    let params = scrypt::Params::new(log_n, r, p, SCRYPT_DKLEN)
+        .map_err(|e| anyhow::anyhow!("invalid scrypt params: {e}"))?;
+    let mut derived = [0u8; 32];
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut derived)
+        .map_err(|e| anyhow::anyhow!("scrypt derivation failed: {e}"))?;
+    Ok(derived)
+}
+
+fn compute_mac(derived_right: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(derived_right);
+    hasher.update(ciphertext);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let sk = SecretKey::from(libsecp256k1::SecretKey::random(&mut rand::thread_rng()));
+        let json = sk.to_encrypted_json("correct horse battery staple").unwrap();
+        let decrypted = SecretKey::from_encrypted_json(&json, "correct horse battery staple").unwrap();
+        assert_eq!(sk, *decrypted);
+    }
+
+    #[test]
+    fn test_wrong_password_rejected() {
+        let sk = SecretKey::from(libsecp256k1::SecretKey::random(&mut rand::thread_rng()));
+        let json = sk.to_encrypted_json("correct horse battery staple").unwrap();
+        assert!(SecretKey::from_encrypted_json(&json, "wrong password").is_err());
+    }
+}
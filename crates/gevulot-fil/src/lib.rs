@@ -5,8 +5,13 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use filecoin_proofs_api::seal::SealCommitPhase1Output;
+use filecoin_proofs_api::seal::SealCommitPhase2Output;
+use filecoin_proofs_api::Commitment;
 use filecoin_proofs_api::ProverId;
+use filecoin_proofs_api::RegisteredAggregationProof;
+use filecoin_proofs_api::RegisteredSealProof;
 use filecoin_proofs_api::SectorId;
+use filecoin_proofs_api::Ticket;
 use gevulot_common::WORKSPACE_PATH;
 use gevulot_node::types::Hash;
 use serde::Deserialize;
@@ -15,6 +20,7 @@ use zeroize::Zeroize;
 use zeroize::ZeroizeOnDrop;
 
 pub mod codec;
+pub mod keystore;
 
 /// Wrapper around a [libsecp256k1::SecretKey] that implements [Zeroize].
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -71,6 +77,26 @@ pub enum C2Input {
         prover_id: ProverId,
         sector_id: SectorId,
     },
+    // V0 of the SnarkPack aggregate commit input, bundling the already-computed
+    // commit phase2 outputs for every sector going into the aggregate proof.
+    AggregateV0 {
+        registered_proof: RegisteredSealProof,
+        registered_aggregation: RegisteredAggregationProof,
+        sectors: Vec<AggregateSectorInput>,
+    },
+}
+
+/// Per-sector input to a SnarkPack aggregate commit, carrying everything
+/// [filecoin_proofs_api::seal::aggregate_seal_commit_proofs] and
+/// [filecoin_proofs_api::seal::verify_aggregate_seal_commit_proofs] need for that sector.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AggregateSectorInput {
+    pub comm_r: Commitment,
+    pub comm_d: Commitment,
+    pub seed: Ticket,
+    pub sector_id: SectorId,
+    pub prover_id: ProverId,
+    pub c2out: SealCommitPhase2Output,
 }
 
 pub fn calc_checksum(data: &[u8]) -> Hash {
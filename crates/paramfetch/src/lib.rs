@@ -0,0 +1,953 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::env;
+use std::fs::create_dir_all;
+use std::fs::rename;
+use std::fs::File;
+use std::io::copy;
+use std::io::stderr;
+use std::io::stdout;
+use std::io::Read;
+use std::io::Stdout;
+use std::io::Write;
+use std::io::{self};
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::ensure;
+use anyhow::Context;
+use anyhow::Result;
+use filecoin_proofs::param::get_digest_for_file_within_cache;
+use filecoin_proofs::param::get_full_path_for_file_within_cache;
+use filecoin_proofs::param::has_extension;
+use flate2::read::GzDecoder;
+use pbr::MultiBar;
+use pbr::ProgressBar;
+use pbr::Units;
+use reqwest::blocking::Client;
+use reqwest::header;
+use reqwest::Proxy;
+use reqwest::Url;
+use sha2::Digest;
+use sha2::Sha256;
+use storage_proofs_core::parameter_cache::parameter_cache_dir;
+use storage_proofs_core::parameter_cache::ParameterMap;
+use storage_proofs_core::parameter_cache::GROTH_PARAMETER_EXT;
+use tar::Archive;
+
+/// Number of attempts (including the first) allowed for a single file transfer
+/// before it's left on disk as missing/invalid for the caller's post-batch
+/// `get_filenames_requiring_download` re-check to pick up on the next pass.
+const MAX_TRANSFER_ATTEMPTS: u32 = 5;
+/// Delay before the first retry of a failed transfer; doubled on each
+/// subsequent attempt, up to [RETRY_MAX_DELAY].
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(16);
+/// Wall-clock budget for a single transfer attempt before it's killed and retried.
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Default, single-entry gateway list used when no gateways are configured.
+pub const DEFAULT_GATEWAY: &str = "https://ipfs.io";
+pub const DEFAULT_IPGET_VERSION: &str = "v0.10.0";
+const DEFAULT_JSON: &str = include_str!("../parameters.json");
+
+/// Number of worker threads to use for a fetch's `jobs` setting when one isn't given
+/// explicitly.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Which backend downloads parameter files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Backend {
+    /// Spawn the external `ipget` binary, downloading it first if necessary.
+    Ipget,
+    /// Fetch `{gateway}/ipfs/{cid}` directly with an in-process HTTP client, trying
+    /// each configured gateway in order. Needs no external binary, so it works in
+    /// minimal container images that can't install or execute `ipget`.
+    Gateway,
+}
+
+/// Which parameter files a caller wants present in the cache.
+#[derive(Debug, Clone)]
+pub enum SectorSelection {
+    /// Every file in the parameter map.
+    All,
+    /// Only `.params` files matching one of these sector sizes, plus every
+    /// verifying-key file (which isn't sector-size specific).
+    Sizes(Vec<bytesize::ByteSize>),
+    /// An explicit, caller-chosen list of filenames.
+    Filenames(Vec<String>),
+}
+
+/// Configuration for a parameter fetch, shared by the `paramfetch` CLI and
+/// embedders like the C2 proving task.
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// Use a specific parameters JSON file instead of the built-in one.
+    pub json_path: Option<String>,
+    /// Path to a JSON file of `{"<filename>": "<source address>"}` overrides; see
+    /// [resolve_param_source].
+    pub source_overrides_path: Option<String>,
+    pub verify: bool,
+    pub verbose: bool,
+    pub jobs: usize,
+    pub backend: Backend,
+    pub gateways: Vec<String>,
+    pub ipget_bin: Option<String>,
+    pub ipget_version: Option<String>,
+    pub ipget_args: Option<String>,
+    /// Number of fetch batches [ensure_parameters] will run before giving up on
+    /// whatever files are still missing.
+    pub max_batches: u32,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            json_path: None,
+            source_overrides_path: None,
+            verify: false,
+            verbose: true,
+            jobs: default_jobs(),
+            backend: Backend::Ipget,
+            gateways: vec![DEFAULT_GATEWAY.to_string()],
+            ipget_bin: None,
+            ipget_version: None,
+            ipget_args: None,
+            max_batches: 1,
+        }
+    }
+}
+
+/// What a fetch actually did, returned by [ensure_parameters].
+#[derive(Debug, Clone, Default)]
+pub struct FetchReport {
+    /// Filenames that were missing or failed verification and were downloaded.
+    pub downloaded: Vec<String>,
+    /// Filenames that were already present and up to date.
+    pub already_present: Vec<String>,
+}
+
+/// Loads a [ParameterMap], either from `json_path` or from the built-in
+/// `parameters.json` bundled with this crate.
+pub fn load_parameter_map(json_path: Option<&str>) -> Result<ParameterMap> {
+    match json_path {
+        Some(json_path) => {
+            let mut json_file =
+                File::open(json_path).with_context(|| format!("open json file {json_path}"))?;
+            serde_json::from_reader(&mut json_file)
+                .with_context(|| format!("parse json file {json_path}"))
+        }
+        None => serde_json::from_str(DEFAULT_JSON).context("parse built-in parameters.json"),
+    }
+}
+
+/// Loads a `--source-overrides`-style `{"<filename>": "<source address>"}` map, or an
+/// empty map if `path` is `None`.
+pub fn load_source_overrides(path: Option<&str>) -> Result<HashMap<String, String>> {
+    match path {
+        Some(path) => {
+            let mut overrides_file =
+                File::open(path).with_context(|| format!("open source overrides file {path}"))?;
+            serde_json::from_reader(&mut overrides_file)
+                .with_context(|| format!("parse source overrides file {path}"))
+        }
+        None => Ok(HashMap::new()),
+    }
+}
+
+/// Resolves a [SectorSelection] against `parameter_map` into the filenames it names.
+pub fn select_filenames(parameter_map: &ParameterMap, selection: &SectorSelection) -> Vec<String> {
+    match selection {
+        SectorSelection::All => parameter_map.keys().cloned().collect(),
+        SectorSelection::Sizes(sizes) => parameter_map
+            .keys()
+            .filter(|filename| {
+                let sector_size = bytesize::ByteSize(parameter_map[*filename].sector_size);
+                !has_extension(filename, GROTH_PARAMETER_EXT) || sizes.contains(&sector_size)
+            })
+            .cloned()
+            .collect(),
+        SectorSelection::Filenames(filenames) => filenames.clone(),
+    }
+}
+
+#[inline]
+fn get_ipget_dir(version: &str) -> String {
+    format!("/var/tmp/ipget-{}", version)
+}
+
+#[inline]
+fn get_ipget_path(version: &str) -> String {
+    format!("{}/ipget/ipget", get_ipget_dir(version))
+}
+
+/// Reader with progress bar.
+struct FetchProgress<R> {
+    reader: R,
+    progress_bar: ProgressBar<Stdout>,
+}
+
+impl<R: Read> Read for FetchProgress<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reader.read(buf).map(|n| {
+            self.progress_bar.add(n as u64);
+            n
+        })
+    }
+}
+
+impl<R: Read> FetchProgress<R> {
+    fn new(reader: R, size: u64) -> Self {
+        let mut progress_bar = ProgressBar::new(size);
+        progress_bar.set_units(Units::Bytes);
+        FetchProgress {
+            reader,
+            progress_bar,
+        }
+    }
+}
+
+/// Directory downloaded ipget archives are cached in, keyed by [cache_key_for_url] so
+/// re-runs across versions/platforms/mirrors never collide.
+const IPGET_DOWNLOAD_CACHE_DIR: &str = "/var/tmp/ipget-dl-cache";
+
+/// Derives a content-addressed cache key for a download URL, so the same URL always
+/// lands on the same cached archive regardless of version or platform.
+fn cache_key_for_url(url: &str) -> String {
+    blake3::hash(url.as_bytes()).to_hex().to_string()
+}
+
+/// Fetches the sha256 of `archive_filename` from the checksums manifest ipget
+/// publishes alongside each release, so the downloaded archive can be verified
+/// before it's trusted and unpacked.
+fn fetch_expected_checksum(client: &Client, version: &str, archive_filename: &str) -> Result<String> {
+    let checksums_url = format!("https://dist.ipfs.io/ipget/{version}/ipget_{version}.txt");
+    let resp = client
+        .get(&checksums_url)
+        .send()
+        .with_context(|| format!("request {checksums_url}"))?;
+    ensure!(
+        resp.status().is_success(),
+        "{checksums_url} returned {}",
+        resp.status()
+    );
+    let body = resp.text().context("read checksums manifest body")?;
+    body.lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let checksum = parts.next()?;
+            let name = parts.next()?;
+            (name == archive_filename).then(|| checksum.to_string())
+        })
+        .with_context(|| format!("no checksum entry for {archive_filename} in {checksums_url}"))
+}
+
+/// Downloads a version of ipget into `get_ipget_dir(version)`.
+///
+/// The downloaded archive is cached under [IPGET_DOWNLOAD_CACHE_DIR] by a key derived
+/// from the download URL, alongside a `.sha256` marker recording the checksum it was
+/// verified against; a cache entry without a matching marker (e.g. left behind by a
+/// process that crashed mid-download) is treated as a cache miss and re-fetched. The
+/// archive is always checksum-verified against ipget's published manifest before
+/// being unpacked, whether it was just downloaded or served from cache.
+pub fn download_ipget(version: &str, verbose: bool) -> Result<()> {
+    println!("downloading ipget");
+
+    let (os, ext) = if cfg!(target_os = "macos") {
+        ("darwin", "tar.gz")
+    } else if cfg!(target_os = "windows") {
+        ("windows", "zip")
+    } else {
+        ("linux", "tar.gz")
+    };
+
+    let archive_filename = format!("ipget_{version}_{os}-amd64.{ext}");
+    let url = format!("https://dist.ipfs.io/ipget/{version}/{archive_filename}");
+    let parsed_url = Url::parse(&url).context("parse ipget download url")?;
+    let client = build_http_client()?;
+
+    let expected_checksum = fetch_expected_checksum(&client, version, &archive_filename)
+        .context("fetch expected ipget archive checksum")?;
+
+    create_dir_all(IPGET_DOWNLOAD_CACHE_DIR).context("create ipget download cache dir")?;
+    let cache_key = cache_key_for_url(&url);
+    let archive_path = format!("{IPGET_DOWNLOAD_CACHE_DIR}/{cache_key}.{ext}");
+    let checksum_marker_path = format!("{archive_path}.sha256");
+
+    let cached_checksum = std::fs::read_to_string(&checksum_marker_path).ok();
+    if Path::new(&archive_path).exists() && cached_checksum.as_deref() == Some(expected_checksum.as_str()) {
+        println!("using cached, verified ipget archive: {archive_path}");
+    } else {
+        // A present-but-unverified cache entry (stale marker, or none at all) is a miss.
+        let _ = std::fs::remove_file(&checksum_marker_path);
+
+        let resp = client
+            .get(parsed_url)
+            .send()
+            .context("request ipget release")?;
+        ensure!(
+            resp.status().is_success(),
+            "non-200 response downloading ipget: {}",
+            resp.status()
+        );
+
+        let size: Option<u64> = resp
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|val| val.to_str().ok())
+            .and_then(|val| val.parse().ok());
+
+        let mut writer =
+            File::create(&archive_path).with_context(|| format!("create {archive_path}"))?;
+        if verbose {
+            if let Some(size) = size {
+                let mut resp_with_progress = FetchProgress::new(resp, size);
+                copy(&mut resp_with_progress, &mut writer).context("write ipget download")?;
+            } else {
+                let mut resp = resp;
+                copy(&mut resp, &mut writer).context("write ipget download")?;
+            }
+        } else {
+            let mut resp = resp;
+            copy(&mut resp, &mut writer).context("write ipget download")?;
+        }
+        drop(writer);
+
+        let downloaded = std::fs::read(&archive_path).context("read downloaded ipget archive")?;
+        let actual_checksum = hex::encode(Sha256::digest(&downloaded));
+        if actual_checksum != expected_checksum {
+            let _ = std::fs::remove_file(&archive_path);
+            bail!(
+                "ipget archive {archive_filename} failed checksum verification: expected {expected_checksum}, got {actual_checksum}"
+            );
+        }
+        std::fs::write(&checksum_marker_path, &expected_checksum)
+            .context("write ipget checksum marker")?;
+        println!("verified ipget archive checksum: {expected_checksum}");
+    }
+
+    let reader = File::open(&archive_path)
+        .with_context(|| format!("open verified archive {archive_path}"))?;
+    if ext == "tar.gz" {
+        let unzipper = GzDecoder::new(reader);
+        let mut unarchiver = Archive::new(unzipper);
+        unarchiver
+            .unpack(get_ipget_dir(version))
+            .context("unpack ipget tar.gz archive")?;
+    } else {
+        let mut zip = zip::ZipArchive::new(reader).context("open ipget zip archive")?;
+        zip.extract(get_ipget_dir(version))
+            .context("extract ipget zip archive")?;
+    }
+    println!(
+        "successfully downloaded ipget binary: {}",
+        get_ipget_path(version),
+    );
+
+    Ok(())
+}
+
+/// Resolves the path to the `ipget` binary, downloading it first if it isn't
+/// already present at the default location and `opts.ipget_bin` wasn't given.
+fn resolve_ipget_path(opts: &FetchOptions) -> Result<PathBuf> {
+    if let Some(path_str) = &opts.ipget_bin {
+        let path = PathBuf::from(path_str);
+        ensure!(
+            path.exists(),
+            "provided ipget binary not found: {}",
+            path.display()
+        );
+        return Ok(path);
+    }
+
+    let ipget_version = opts
+        .ipget_version
+        .clone()
+        .unwrap_or_else(|| DEFAULT_IPGET_VERSION.to_string());
+    let path = PathBuf::from(get_ipget_path(&ipget_version));
+    if !path.exists() {
+        println!("ipget binary not found: {}", path.display());
+        download_ipget(&ipget_version, opts.verbose).context("download ipget")?;
+    }
+    Ok(path)
+}
+
+/// Check which files are outdated (or do not exist).
+pub fn get_filenames_requiring_download(
+    parameter_map: &ParameterMap,
+    selected_filenames: Vec<String>,
+    verify: bool,
+) -> Result<Vec<String>> {
+    let mut requiring_download = Vec::with_capacity(selected_filenames.len());
+    for filename in selected_filenames {
+        let path = get_full_path_for_file_within_cache(&filename);
+        if !path.exists() {
+            requiring_download.push(filename);
+            continue;
+        }
+        if !verify {
+            continue;
+        }
+
+        println!("calculating digest for {}", path.display());
+        let calculated_digest = match get_digest_for_file_within_cache(&filename) {
+            Ok(digest) => digest,
+            Err(e) => {
+                eprintln!("failed to hash file {}, marking for download", e);
+                requiring_download.push(filename);
+                continue;
+            }
+        };
+        let expected_digest = &parameter_map[&filename].digest;
+        if &calculated_digest == expected_digest {
+            continue;
+        }
+
+        println!("file has unexpected digest, marking for download");
+        let new_filename = format!("{}-invalid-digest", filename);
+        let new_path = path.with_file_name(new_filename);
+        println!("moving invalid params to: {}", new_path.display());
+        rename(&path, &new_path)
+            .with_context(|| format!("move invalid params {} to {}", path.display(), new_path.display()))?;
+        requiring_download.push(filename);
+    }
+    Ok(requiring_download)
+}
+
+fn download_file_with_ipget(
+    cid: &str,
+    path: &Path,
+    ipget_path: &Path,
+    ipget_args: &Option<String>,
+    verbose: bool,
+    timeout: Duration,
+) -> Result<()> {
+    // IPFS_GATEWAY=https://proof-parameters.s3.cn-south-1.jdcloud-oss.com/ipfs/
+    let url = if let Ok(gw) = env::var("IPFS_GATEWAY") {
+        format!("{}/{}", gw.trim_end_matches('/'), cid)
+    } else {
+        cid.to_string()
+    };
+    let mut args = vec![url, "-o".to_string(), path.display().to_string()];
+    if let Some(ipget_args) = ipget_args {
+        args.extend(ipget_args.split_whitespace().map(|x| x.to_string()));
+    }
+    let progress_flag = "--progress".to_string();
+    if verbose && !args.contains(&progress_flag) {
+        args.push(progress_flag);
+    }
+    println!(
+        "spawning subprocess: {} {}",
+        ipget_path.display(),
+        args.join(" ")
+    );
+
+    let mut child = Command::new(ipget_path.as_os_str())
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| "failed to spawn ipget subprocess")?;
+
+    // Drain the child's pipes on their own threads while we poll for exit below, so a
+    // chatty `--progress` subprocess can't deadlock us by filling its stdout/stderr
+    // buffer before we get around to reading it.
+    let mut child_stdout = child.stdout.take().expect("stdout was piped");
+    let mut child_stderr = child.stderr.take().expect("stderr was piped");
+    let stdout_reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = child_stdout.read_to_end(&mut buf);
+        buf
+    });
+    let stderr_reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = child_stderr.read_to_end(&mut buf);
+        buf
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().context("poll ipget subprocess")? {
+            break status;
+        }
+        if start.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("ipget subprocess for {cid} timed out after {timeout:?}");
+        }
+        thread::sleep(Duration::from_millis(200));
+    };
+
+    let out = stdout_reader.join().unwrap_or_default();
+    let err = stderr_reader.join().unwrap_or_default();
+    if verbose {
+        stdout()
+            .write_all(&out)
+            .with_context(|| "failed to write ipget's stdout")?;
+        stderr()
+            .write_all(&err)
+            .with_context(|| "failed to write ipget's stderr")?;
+    }
+    ensure!(status.success(), "ipget returned non-zero exit code");
+    Ok(())
+}
+
+/// Downloads `cid` from `gateway` (e.g. `https://ipfs.io`) by requesting
+/// `{gateway}/ipfs/{cid}` and streaming the response body into `path` through
+/// [FetchProgress], the same reader the ipget-binary bootstrap uses for its
+/// byte-level progress bar.
+fn download_file_from_gateway(
+    client: &Client,
+    gateway: &str,
+    cid: &str,
+    path: &Path,
+    verbose: bool,
+) -> Result<()> {
+    let url = format!("{}/ipfs/{}", gateway.trim_end_matches('/'), cid);
+    fetch_url_to_file(client, &url, path, verbose)
+        .with_context(|| format!("fetch {url} from gateway {gateway}"))
+}
+
+/// Requests `url` and streams the response body into `path` through
+/// [FetchProgress] (when `verbose` and a `Content-Length` is known), the same
+/// reader the ipget-binary bootstrap uses for its byte-level progress bar.
+fn fetch_url_to_file(client: &Client, url: &str, path: &Path, verbose: bool) -> Result<()> {
+    let resp = client
+        .get(url)
+        .send()
+        .with_context(|| format!("request {url}"))?;
+    ensure!(resp.status().is_success(), "{url} returned {}", resp.status());
+
+    let size: Option<u64> = resp
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|val| val.to_str().ok())
+        .and_then(|val| val.parse().ok());
+
+    let mut writer = File::create(path).with_context(|| format!("create {}", path.display()))?;
+    if verbose {
+        if let Some(size) = size {
+            let mut resp_with_progress = FetchProgress::new(resp, size);
+            copy(&mut resp_with_progress, &mut writer).context("write downloaded file")?;
+            return Ok(());
+        }
+    }
+    let mut resp = resp;
+    copy(&mut resp, &mut writer).context("write downloaded file")?;
+    Ok(())
+}
+
+/// Tries each of `gateways` in order for `filename`, accepting the first one whose
+/// downloaded bytes verify against `parameter_map[filename].digest` via the same
+/// [get_digest_for_file_within_cache] check used elsewhere. A gateway that fails to
+/// respond or serves a file that fails verification is discarded and the next
+/// gateway in the list is tried, so a flaky public gateway falls back to e.g. a
+/// private S3/OSS mirror instead of failing the whole transfer.
+fn download_file_via_gateways(
+    client: &Client,
+    gateways: &[String],
+    parameter_map: &ParameterMap,
+    filename: &str,
+    path: &Path,
+    verbose: bool,
+) -> Result<()> {
+    ensure!(
+        !gateways.is_empty(),
+        "no gateways configured for the gateway backend"
+    );
+    let cid = &parameter_map[filename].cid;
+    let expected_digest = &parameter_map[filename].digest;
+
+    let mut last_err = None;
+    for gateway in gateways {
+        if let Err(err) = download_file_from_gateway(client, gateway, cid, path, verbose) {
+            eprintln!("{gateway}: download failed: {err:#}, trying next gateway");
+            last_err = Some(err);
+            continue;
+        }
+
+        match get_digest_for_file_within_cache(filename) {
+            Ok(digest) if &digest == expected_digest => return Ok(()),
+            Ok(_) => {
+                eprintln!("{gateway}: downloaded file failed digest verification, trying next gateway");
+                let _ = std::fs::remove_file(path);
+                last_err = Some(anyhow!(
+                    "{gateway}: downloaded file failed digest verification"
+                ));
+            }
+            Err(err) => {
+                let _ = std::fs::remove_file(path);
+                last_err = Some(err.context(format!("{gateway}: hash downloaded file")));
+            }
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| anyhow!("all configured gateways failed for {filename}")))
+}
+
+/// A single place the cache can pull a parameter file's bytes from, resolved by
+/// [resolve_param_source] from a source address's URI scheme. Lets a `ParameterMap`
+/// entry's CID and any source-override address be handled uniformly by the
+/// scheduler, keeping `get_filenames_requiring_download` backend-agnostic: it only
+/// ever looks at the digest on disk, never at where the bytes came from.
+trait ParamSource: Send + Sync {
+    /// Cheap existence probe for the source itself (not the destination), so an
+    /// obviously-missing source (e.g. a `file://` path that isn't there) can be
+    /// rejected before spending a retry budget on it.
+    fn exists(&self) -> bool;
+
+    /// Fetches (copies/hardlinks/downloads) this source's bytes to `dest`,
+    /// overwriting whatever is already there.
+    fn fetch(&self, dest: &Path, verbose: bool) -> Result<()>;
+}
+
+/// An IPFS CID, fetched through whichever backend (`ipget` subprocess or HTTP
+/// gateway) was configured.
+struct IpfsSource {
+    filename: String,
+    cid: String,
+    transfer: Arc<TransferFn>,
+}
+
+impl ParamSource for IpfsSource {
+    fn exists(&self) -> bool {
+        true
+    }
+
+    fn fetch(&self, dest: &Path, _verbose: bool) -> Result<()> {
+        (self.transfer)(&self.filename, &self.cid, dest)
+    }
+}
+
+/// A file already present on a local (or network-mounted) filesystem. Hardlinked
+/// into the cache when possible, falling back to a copy across filesystems.
+struct FileSource {
+    path: PathBuf,
+}
+
+impl ParamSource for FileSource {
+    fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    fn fetch(&self, dest: &Path, _verbose: bool) -> Result<()> {
+        if let Some(parent) = dest.parent() {
+            create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+        }
+        let _ = std::fs::remove_file(dest);
+        if std::fs::hard_link(&self.path, dest).is_err() {
+            std::fs::copy(&self.path, dest).with_context(|| {
+                format!("copy {} to {}", self.path.display(), dest.display())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// A plain HTTP(S) (or S3, resolved to an HTTP endpoint) object, fetched with a
+/// single GET and no gateway fallback.
+struct HttpSource {
+    client: Client,
+    url: String,
+}
+
+impl ParamSource for HttpSource {
+    fn exists(&self) -> bool {
+        true
+    }
+
+    fn fetch(&self, dest: &Path, verbose: bool) -> Result<()> {
+        fetch_url_to_file(&self.client, &self.url, dest, verbose)
+    }
+}
+
+/// Parses a source address into the concrete [ParamSource] it names, dispatching
+/// purely on URI scheme:
+/// - `ipfs://<cid>` or a bare CID (no `://`) -> an IPFS CID, fetched via `transfer`
+/// - `file:///path` -> a local copy/hardlink
+/// - `http://`/`https://` -> a plain GET
+/// - `s3://bucket/key` -> resolved to `{$PARAM_S3_ENDPOINT}/bucket/key` (default
+///   `https://s3.amazonaws.com`) and fetched like `https://`
+fn resolve_param_source(
+    addr: &str,
+    filename: &str,
+    http_client: &Client,
+    transfer: &Arc<TransferFn>,
+) -> Result<Box<dyn ParamSource>> {
+    if let Some(cid) = addr.strip_prefix("ipfs://") {
+        return Ok(Box::new(IpfsSource {
+            filename: filename.to_string(),
+            cid: cid.to_string(),
+            transfer: Arc::clone(transfer),
+        }));
+    }
+    if let Some(path) = addr.strip_prefix("file://") {
+        return Ok(Box::new(FileSource {
+            path: PathBuf::from(path),
+        }));
+    }
+    if let Some(rest) = addr.strip_prefix("s3://") {
+        let (bucket, key) = rest
+            .split_once('/')
+            .with_context(|| format!("s3 address missing key: expected s3://bucket/key, got {addr}"))?;
+        let endpoint =
+            env::var("PARAM_S3_ENDPOINT").unwrap_or_else(|_| "https://s3.amazonaws.com".to_string());
+        return Ok(Box::new(HttpSource {
+            client: http_client.clone(),
+            url: format!("{}/{bucket}/{key}", endpoint.trim_end_matches('/')),
+        }));
+    }
+    if addr.starts_with("http://") || addr.starts_with("https://") {
+        return Ok(Box::new(HttpSource {
+            client: http_client.clone(),
+            url: addr.to_string(),
+        }));
+    }
+    if !addr.contains("://") {
+        // A bare CID, same as `ipfs://<cid>`.
+        return Ok(Box::new(IpfsSource {
+            filename: filename.to_string(),
+            cid: addr.to_string(),
+            transfer: Arc::clone(transfer),
+        }));
+    }
+    bail!("unsupported parameter source address: {addr}");
+}
+
+/// A single transfer attempt, abstracted over how bytes actually move (the
+/// `ipget` subprocess or a native gateway fetch) and taking `(filename, cid,
+/// destination path)` so either backend can look up whatever it needs from the
+/// parameter map. Keeping the scheduler below generic over this closure means
+/// neither backend has to know about threads, retries, or progress bars.
+type TransferFn = dyn Fn(&str, &str, &Path) -> Result<()> + Send + Sync;
+
+/// Outcome reported by a worker thread once a file transfer (successful or
+/// exhausted its retries) is done.
+struct TransferDone {
+    filename: String,
+    result: Result<()>,
+}
+
+fn build_http_client() -> Result<Client> {
+    Client::builder()
+        .proxy(Proxy::custom(move |url| env_proxy::for_url(url).to_url()))
+        .build()
+        .context("build http client")
+}
+
+/// Builds the `(filename) -> ParamSource` resolver for `opts`, wiring up whichever
+/// `--backend` an IPFS CID should go through and the HTTP client used for
+/// `s3://`/`http(s)://` source-override addresses.
+fn build_resolver(
+    opts: &FetchOptions,
+    parameter_map: &Arc<ParameterMap>,
+    source_overrides: HashMap<String, String>,
+) -> Result<Arc<dyn Fn(&str) -> Result<Box<dyn ParamSource>> + Send + Sync>> {
+    let verbose = opts.verbose;
+    let transfer: Arc<TransferFn> = match opts.backend {
+        Backend::Ipget => {
+            let ipget_path = resolve_ipget_path(opts)?;
+            println!("using ipget binary: {}", ipget_path.display());
+            let ipget_args = opts.ipget_args.clone();
+            Arc::new(move |_filename: &str, cid: &str, path: &Path| {
+                download_file_with_ipget(
+                    cid,
+                    path,
+                    &ipget_path,
+                    &ipget_args,
+                    verbose,
+                    TRANSFER_TIMEOUT,
+                )
+            })
+        }
+        Backend::Gateway => {
+            println!("using gateway backend, gateways: {:?}", opts.gateways);
+            let client = build_http_client()?;
+            let gateways = opts.gateways.clone();
+            let parameter_map = Arc::clone(parameter_map);
+            Arc::new(move |filename: &str, _cid: &str, path: &Path| {
+                download_file_via_gateways(&client, &gateways, &parameter_map, filename, path, verbose)
+            })
+        }
+    };
+
+    let http_client = build_http_client()?;
+    let parameter_map = Arc::clone(parameter_map);
+    Ok(Arc::new(move |filename: &str| {
+        let addr = source_overrides
+            .get(filename)
+            .cloned()
+            .unwrap_or_else(|| format!("ipfs://{}", parameter_map[filename].cid));
+        resolve_param_source(&addr, filename, &http_client, &transfer)
+    }))
+}
+
+/// Downloads `filenames` with up to `jobs` transfers in flight at once. Modeled on a
+/// curl-multi style event loop: a shared queue of pending filenames feeds a bounded
+/// set of worker threads, and whenever a worker finishes its current file it pops the
+/// next pending filename in to replace it, until the queue and the active set are both
+/// empty. Each transfer gets its own wall-clock timeout and retries with exponential
+/// backoff; a file that exhausts [MAX_TRANSFER_ATTEMPTS] is simply left as-is rather
+/// than aborting the batch, so the caller's usual `get_filenames_requiring_download`
+/// re-check after the batch picks it up again. Shows a multi-bar display: one line per
+/// worker plus an aggregate bar of files completed.
+fn download_all(
+    filenames: &[String],
+    jobs: usize,
+    resolve: Arc<dyn Fn(&str) -> Result<Box<dyn ParamSource>> + Send + Sync>,
+    verbose: bool,
+) {
+    if filenames.is_empty() {
+        return;
+    }
+    let jobs = jobs.clamp(1, filenames.len());
+    let queue = Arc::new(Mutex::new(VecDeque::from(filenames.to_vec())));
+    let (done_tx, done_rx) = mpsc::channel::<TransferDone>();
+
+    let mut multi_bar = MultiBar::new();
+    let mut total_bar = multi_bar.create_bar(filenames.len() as u64);
+    total_bar.message("files downloaded ");
+
+    let workers: Vec<_> = (0..jobs)
+        .map(|slot| {
+            let queue = Arc::clone(&queue);
+            let resolve = Arc::clone(&resolve);
+            let done_tx = done_tx.clone();
+            let mut bar = multi_bar.create_bar(0);
+            bar.show_message = true;
+            bar.message(&format!("[worker {slot}] idle "));
+            bar.tick();
+
+            thread::spawn(move || {
+                while let Some(filename) = queue.lock().expect("queue poisoned").pop_front() {
+                    let path = get_full_path_for_file_within_cache(&filename);
+
+                    // Reject an obviously-missing source before spending a retry budget on it.
+                    if matches!(resolve(&filename), Ok(source) if !source.exists()) {
+                        eprintln!("[worker {slot}] {filename}: source doesn't exist, skipping retries");
+                        let result = Err(anyhow!("source for {filename} doesn't exist"));
+                        let _ = done_tx.send(TransferDone { filename, result });
+                        continue;
+                    }
+
+                    let mut delay = RETRY_BASE_DELAY;
+                    let mut result = Err(anyhow!("no attempt made"));
+                    for attempt in 1..=MAX_TRANSFER_ATTEMPTS {
+                        bar.message(&format!(
+                            "[worker {slot}] {filename} (attempt {attempt}/{MAX_TRANSFER_ATTEMPTS}) "
+                        ));
+                        bar.tick();
+                        result =
+                            resolve(&filename).and_then(|source| source.fetch(&path, verbose));
+                        if result.is_ok() || attempt == MAX_TRANSFER_ATTEMPTS {
+                            break;
+                        }
+                        eprintln!(
+                            "[worker {slot}] {filename}: attempt {attempt}/{MAX_TRANSFER_ATTEMPTS} failed: {:#}, retrying in {delay:?}",
+                            result.as_ref().unwrap_err()
+                        );
+                        thread::sleep(delay);
+                        delay = (delay * 2).min(RETRY_MAX_DELAY);
+                    }
+
+                    let _ = done_tx.send(TransferDone { filename, result });
+                }
+                bar.finish_println(&format!("[worker {slot}] idle, no more files"));
+            })
+        })
+        .collect();
+    drop(done_tx);
+
+    let listener = thread::spawn(move || multi_bar.listen());
+
+    for done in done_rx {
+        total_bar.inc();
+        if let Err(err) = done.result {
+            eprintln!(
+                "giving up on {} after {MAX_TRANSFER_ATTEMPTS} attempts: {err:#}",
+                done.filename
+            );
+        }
+    }
+    total_bar.finish();
+
+    for worker in workers {
+        let _ = worker.join();
+    }
+    let _ = listener.join();
+}
+
+/// Runs a single fetch batch for `filenames` against `opts`/`parameter_map` and
+/// returns whichever of them are still missing or invalid afterwards. Used both by
+/// [ensure_parameters] (which retries on the caller's behalf) and by the CLI, which
+/// retries interactively.
+pub fn fetch_once(
+    filenames: &[String],
+    parameter_map: &Arc<ParameterMap>,
+    opts: &FetchOptions,
+) -> Result<Vec<String>> {
+    let source_overrides = load_source_overrides(opts.source_overrides_path.as_deref())?;
+    let resolve = build_resolver(opts, parameter_map, source_overrides)?;
+    create_dir_all(parameter_cache_dir()).context("create param cache dir")?;
+    download_all(filenames, opts.jobs, resolve, opts.verbose);
+    get_filenames_requiring_download(parameter_map, filenames.to_vec(), opts.verify)
+}
+
+/// Ensures every parameter file named by `selection` is present and (if
+/// `opts.verify`) passes digest verification, fetching whatever is missing.
+///
+/// Unlike the interactive CLI, this never prompts: it retries up to
+/// `opts.max_batches` times and then returns an error naming whatever files are
+/// still missing, so a caller like a proving task gets a clean failure instead of
+/// a late, confusing error partway through proving.
+pub fn ensure_parameters(selection: SectorSelection, opts: FetchOptions) -> Result<FetchReport> {
+    let parameter_map = Arc::new(load_parameter_map(opts.json_path.as_deref())?);
+
+    let selected = select_filenames(&parameter_map, &selection);
+    let mut missing = get_filenames_requiring_download(&parameter_map, selected.clone(), opts.verify)?;
+    let already_present: Vec<String> = selected
+        .into_iter()
+        .filter(|filename| !missing.contains(filename))
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(FetchReport {
+            downloaded: vec![],
+            already_present,
+        });
+    }
+    let attempted = missing.clone();
+
+    for _ in 0..opts.max_batches.max(1) {
+        missing = fetch_once(&missing, &parameter_map, &opts)?;
+        if missing.is_empty() {
+            break;
+        }
+    }
+
+    ensure!(
+        missing.is_empty(),
+        "failed to fetch {} of {} required parameter file(s) after {} attempt(s): {:?}",
+        missing.len(),
+        attempted.len(),
+        opts.max_batches.max(1),
+        missing
+    );
+
+    Ok(FetchReport {
+        downloaded: attempted,
+        already_present,
+    })
+}
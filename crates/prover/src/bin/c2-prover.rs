@@ -58,5 +58,26 @@ fn run_task(task: Task) -> Result<TaskResult> {
                 .with_context(|| format!("write proof to {}", proof_output))?;
             task.result(c2out.proof, vec![proof_output.to_string()])
         }
+        C2Input::AggregateV0 {
+            registered_proof,
+            registered_aggregation,
+            sectors,
+        } => {
+            let comm_rs: Vec<_> = sectors.iter().map(|s| s.comm_r).collect();
+            let seeds: Vec<_> = sectors.iter().map(|s| s.seed).collect();
+            let commit_outputs: Vec<_> = sectors.into_iter().map(|s| s.c2out).collect();
+
+            let aggregate_proof = seal::aggregate_seal_commit_proofs(
+                registered_proof,
+                registered_aggregation,
+                &comm_rs,
+                &seeds,
+                &commit_outputs,
+            )
+            .context("run aggregate_seal_commit_proofs")?;
+            fs::write(workspace(proof_output), &aggregate_proof)
+                .with_context(|| format!("write proof to {}", proof_output))?;
+            task.result(aggregate_proof, vec![proof_output.to_string()])
+        }
     }
 }
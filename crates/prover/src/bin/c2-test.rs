@@ -23,6 +23,13 @@ fn main() -> Result<()> {
             prover_id,
             sector_id,
         } => {
+            let sector_size = bytesize::ByteSize(u64::from(c1out.registered_proof.sector_size()));
+            paramfetch::ensure_parameters(
+                paramfetch::SectorSelection::Sizes(vec![sector_size]),
+                paramfetch::FetchOptions::default(),
+            )
+            .context("ensure groth parameters are present for this sector size")?;
+
             let c2out = seal::seal_commit_phase2(c1out, prover_id, sector_id)
                 .context("run seal_commit_phase2")?;
             fs::write(workspace(&proof_output), &c2out.proof)
@@ -30,6 +37,34 @@ fn main() -> Result<()> {
             // task.result(c2out.proof, vec![proof_output.to_string()])
             println!("{:?}", c2out.proof);
         }
+        C2Input::AggregateV0 {
+            registered_proof,
+            registered_aggregation,
+            sectors,
+        } => {
+            let sector_size = bytesize::ByteSize(u64::from(registered_proof.sector_size()));
+            paramfetch::ensure_parameters(
+                paramfetch::SectorSelection::Sizes(vec![sector_size]),
+                paramfetch::FetchOptions::default(),
+            )
+            .context("ensure groth parameters are present for this sector size")?;
+
+            let comm_rs: Vec<_> = sectors.iter().map(|s| s.comm_r).collect();
+            let seeds: Vec<_> = sectors.iter().map(|s| s.seed).collect();
+            let commit_outputs: Vec<_> = sectors.into_iter().map(|s| s.c2out).collect();
+
+            let aggregate_proof = seal::aggregate_seal_commit_proofs(
+                registered_proof,
+                registered_aggregation,
+                &comm_rs,
+                &seeds,
+                &commit_outputs,
+            )
+            .context("run aggregate_seal_commit_proofs")?;
+            fs::write(workspace(&proof_output), &aggregate_proof)
+                .with_context(|| format!("write proof to {}", proof_output))?;
+            println!("{:?}", aggregate_proof);
+        }
     }
 
     Ok(())
@@ -68,5 +68,28 @@ fn run_task(task: Task) -> Result<TaskResult> {
             .context("verify seal")?;
             task.result(proof, vec![])
         }
+        C2Input::AggregateV0 {
+            registered_proof,
+            registered_aggregation,
+            sectors,
+        } => {
+            let comm_rs: Vec<_> = sectors.iter().map(|s| s.comm_r).collect();
+            let comm_ds: Vec<_> = sectors.iter().map(|s| s.comm_d).collect();
+            let seeds: Vec<_> = sectors.iter().map(|s| s.seed).collect();
+            let commit_outputs: Vec<_> = sectors.into_iter().map(|s| s.c2out).collect();
+
+            seal::verify_aggregate_seal_commit_proofs(
+                registered_proof,
+                registered_aggregation,
+                proof.clone(),
+                &comm_rs,
+                &comm_ds,
+                &seeds,
+                commit_outputs,
+            )
+            .and_then(|x| x.then_some(()).ok_or(anyhow!("invalid aggregate proof")))
+            .context("verify aggregate seal commit proofs")?;
+            task.result(proof, vec![])
+        }
     }
 }
@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use anyhow::Result;
+use bellperson::groth16::PreparedVerifyingKey;
+use blstrs::Bls12;
 use filecoin_proofs::caches::lookup_groth_params;
+use filecoin_proofs::caches::lookup_verifying_key;
 use filecoin_proofs::parameters::window_post_public_params;
 use filecoin_proofs::parameters::winning_post_public_params;
 use filecoin_proofs::MerkleTreeTrait;
@@ -58,3 +61,48 @@ pub(crate) fn get_post_params<Tree: 'static + MerkleTreeTrait>(
         }
     }
 }
+
+pub(crate) fn get_post_verifying_key<Tree: 'static + MerkleTreeTrait>(
+    post_config: &PoStConfig,
+) -> Result<Arc<PreparedVerifyingKey<Bls12>>> {
+    match post_config.typ {
+        PoStType::Winning => {
+            let post_public_params = winning_post_public_params::<Tree>(post_config)?;
+
+            let vk_generator = || {
+                <FallbackPoStCompound<Tree> as CompoundProof<
+                    FallbackPoSt<'_, Tree>,
+                    FallbackPoStCircuit<Tree>,
+                >>::verifying_key::<OsRng>(None, &post_public_params)
+                .map_err(Into::into)
+            };
+
+            Ok(lookup_verifying_key(
+                format!(
+                    "WINNING_POST[{}]",
+                    usize::from(post_config.padded_sector_size())
+                ),
+                vk_generator,
+            )?)
+        }
+        PoStType::Window => {
+            let post_public_params = window_post_public_params::<Tree>(post_config)?;
+
+            let vk_generator = || {
+                <FallbackPoStCompound<Tree> as CompoundProof<
+                    FallbackPoSt<'_, Tree>,
+                    FallbackPoStCircuit<Tree>,
+                >>::verifying_key::<OsRng>(None, &post_public_params)
+                .map_err(Into::into)
+            };
+
+            Ok(lookup_verifying_key(
+                format!(
+                    "Window_POST[{}]",
+                    usize::from(post_config.padded_sector_size())
+                ),
+                vk_generator,
+            )?)
+        }
+    }
+}
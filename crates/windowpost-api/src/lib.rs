@@ -68,7 +68,7 @@ impl VanillaPoStProofs {
                     raw_proofs.capacity(),
                 ))
             } else if typeid::of::<Tree>() == typeid::of::<SectorShape64GiB>() {
-                VanillaPoStProofs::PoSt32GiBV1(Vec::from_raw_parts(
+                VanillaPoStProofs::PoSt64GiBV1(Vec::from_raw_parts(
                     raw_proofs.as_mut_ptr().cast(),
                     raw_proofs.len(),
                     raw_proofs.capacity(),
@@ -242,3 +242,322 @@ fn generate_window_post_snark_proof_inner<Tree: 'static + MerkleTreeTrait>(
         raw_vanilla_proofs,
     )
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum VanillaWinningPoStProofs {
+    PoSt2KiBV1(Vec<RawVanillaPoStProof<SectorShape2KiB>>),
+    PoSt8MiBV1(Vec<RawVanillaPoStProof<SectorShape8MiB>>),
+    PoSt512MiBV1(Vec<RawVanillaPoStProof<SectorShape512MiB>>),
+    PoSt32GiBV1(Vec<RawVanillaPoStProof<SectorShape32GiB>>),
+    PoSt64GiBV1(Vec<RawVanillaPoStProof<SectorShape64GiB>>),
+}
+
+impl VanillaWinningPoStProofs {
+    pub fn try_from_raw<Tree: 'static + MerkleTreeTrait>(
+        mut raw_proofs: Vec<RawVanillaPoStProof<Tree>>,
+    ) -> Result<Self> {
+        unsafe {
+            Ok(if typeid::of::<Tree>() == typeid::of::<SectorShape2KiB>() {
+                VanillaWinningPoStProofs::PoSt2KiBV1(Vec::from_raw_parts(
+                    raw_proofs.as_mut_ptr().cast(),
+                    raw_proofs.len(),
+                    raw_proofs.capacity(),
+                ))
+            } else if typeid::of::<Tree>() == typeid::of::<SectorShape8MiB>() {
+                VanillaWinningPoStProofs::PoSt8MiBV1(Vec::from_raw_parts(
+                    raw_proofs.as_mut_ptr().cast(),
+                    raw_proofs.len(),
+                    raw_proofs.capacity(),
+                ))
+            } else if typeid::of::<Tree>() == typeid::of::<SectorShape512MiB>() {
+                VanillaWinningPoStProofs::PoSt512MiBV1(Vec::from_raw_parts(
+                    raw_proofs.as_mut_ptr().cast(),
+                    raw_proofs.len(),
+                    raw_proofs.capacity(),
+                ))
+            } else if typeid::of::<Tree>() == typeid::of::<SectorShape32GiB>() {
+                VanillaWinningPoStProofs::PoSt32GiBV1(Vec::from_raw_parts(
+                    raw_proofs.as_mut_ptr().cast(),
+                    raw_proofs.len(),
+                    raw_proofs.capacity(),
+                ))
+            } else if typeid::of::<Tree>() == typeid::of::<SectorShape64GiB>() {
+                VanillaWinningPoStProofs::PoSt64GiBV1(Vec::from_raw_parts(
+                    raw_proofs.as_mut_ptr().cast(),
+                    raw_proofs.len(),
+                    raw_proofs.capacity(),
+                ))
+            } else {
+                bail!("invalid proofs provided")
+            })
+        }
+    }
+
+    pub fn try_into_raw<Tree: 'static + MerkleTreeTrait>(
+        self,
+    ) -> Result<Vec<RawVanillaPoStProof<Tree>>> {
+        Ok(unsafe {
+            match self {
+                VanillaWinningPoStProofs::PoSt2KiBV1(mut x)
+                    if typeid::of::<Tree>() == typeid::of::<SectorShape2KiB>() =>
+                {
+                    Vec::from_raw_parts(x.as_mut_ptr().cast(), x.len(), x.capacity())
+                }
+                VanillaWinningPoStProofs::PoSt8MiBV1(mut x)
+                    if typeid::of::<Tree>() == typeid::of::<SectorShape8MiB>() =>
+                {
+                    Vec::from_raw_parts(x.as_mut_ptr().cast(), x.len(), x.capacity())
+                }
+                VanillaWinningPoStProofs::PoSt512MiBV1(mut x)
+                    if typeid::of::<Tree>() == typeid::of::<SectorShape512MiB>() =>
+                {
+                    Vec::from_raw_parts(x.as_mut_ptr().cast(), x.len(), x.capacity())
+                }
+                VanillaWinningPoStProofs::PoSt32GiBV1(mut x)
+                    if typeid::of::<Tree>() == typeid::of::<SectorShape32GiB>() =>
+                {
+                    Vec::from_raw_parts(x.as_mut_ptr().cast(), x.len(), x.capacity())
+                }
+                VanillaWinningPoStProofs::PoSt64GiBV1(mut x)
+                    if typeid::of::<Tree>() == typeid::of::<SectorShape64GiB>() =>
+                {
+                    Vec::from_raw_parts(x.as_mut_ptr().cast(), x.len(), x.capacity())
+                }
+                _ => {
+                    bail!("invalid proofs provided")
+                }
+            }
+        })
+    }
+}
+
+/// Generates a Winning Proof-of-Spacetime.
+///
+/// Unlike Window PoSt, which proves all of a miner's sectors on a periodic schedule,
+/// Winning PoSt is generated at block-production time against a single sector/partition
+/// selected by the challenge randomness, so low latency matters more than throughput.
+///
+/// # Arguments
+/// * `randomness` - Random seed value for PoSt challenge.
+/// * `replicas` - The challenged replica(s) to generate a proof for.
+/// * `prover_id` - Unique ID of the storage provider.
+///
+/// Returns [`SnarkProof`] for challenge.
+pub fn generate_winning_post_vanilla_proofs(
+    proof_type: RegisteredPoStProof,
+    randomness: &ChallengeSeed,
+    replicas: &BTreeMap<SectorId, crate::types::PrivateReplicaInfo>,
+    prover_id: ProverId,
+) -> Result<(Vec<PublicSector<DefaultTreeDomain>>, VanillaWinningPoStProofs)> {
+    ensure!(!replicas.is_empty(), "no replicas supplied");
+    let registered_post_proof_type_v1 = replicas
+        .values()
+        .next()
+        .map(|v| v.registered_proof)
+        .expect("replica map failure");
+    ensure!(
+        registered_post_proof_type_v1.typ() == PoStType::Winning,
+        "invalid post type provided"
+    );
+
+    with_shape!(
+        u64::from(registered_post_proof_type_v1.sector_size()),
+        generate_winning_post_vanilla_proofs_inner,
+        proof_type,
+        randomness,
+        replicas,
+        prover_id,
+    )
+}
+
+fn generate_winning_post_vanilla_proofs_inner<Tree: 'static + MerkleTreeTrait>(
+    proof_type: RegisteredPoStProof,
+    randomness: &ChallengeSeed,
+    replicas: &BTreeMap<SectorId, crate::types::PrivateReplicaInfo>,
+    prover_id: ProverId,
+) -> Result<(Vec<PublicSector<DefaultTreeDomain>>, VanillaWinningPoStProofs)> {
+    let mut replicas_v1 = BTreeMap::new();
+
+    for (id, info) in replicas.iter() {
+        let crate::types::PrivateReplicaInfo {
+            registered_proof,
+            comm_r,
+            cache_dir,
+            replica_path,
+        } = info;
+
+        ensure!(
+            registered_proof == &proof_type,
+            "can only generate the same kind of PoSt"
+        );
+        let info_v1 =
+            PrivateReplicaInfo::<Tree>::new(replica_path.clone(), *comm_r, cache_dir.into())?;
+
+        replicas_v1.insert(*id, info_v1);
+    }
+
+    ensure!(!replicas_v1.is_empty(), "missing v1 replicas");
+    let post_config = proof_type.as_v1_config();
+    let (mut pub_sectors, raw_vanilla_proofs) = proof::generate_winning_post_vanilla_proofs(
+        &post_config,
+        randomness,
+        &replicas_v1,
+        prover_id,
+    )?;
+
+    Ok((
+        unsafe {
+            Vec::from_raw_parts(
+                pub_sectors.as_mut_ptr().cast(),
+                pub_sectors.len(),
+                pub_sectors.capacity(),
+            )
+        },
+        VanillaWinningPoStProofs::try_from_raw::<Tree>(raw_vanilla_proofs)?,
+    ))
+}
+
+pub fn generate_winning_post_snark_proof<Tree: 'static + MerkleTreeTrait>(
+    proof_type: RegisteredPoStProof,
+    randomness: &ChallengeSeed,
+    prover_id: ProverId,
+    pub_sectors: Vec<PublicSector<DefaultTreeDomain>>,
+    vanilla_proofs: VanillaWinningPoStProofs,
+) -> Result<SnarkProof> {
+    with_shape!(
+        u64::from(proof_type.sector_size()),
+        generate_winning_post_snark_proof_inner,
+        proof_type,
+        randomness,
+        prover_id,
+        pub_sectors,
+        vanilla_proofs,
+    )
+}
+
+fn generate_winning_post_snark_proof_inner<Tree: 'static + MerkleTreeTrait>(
+    proof_type: RegisteredPoStProof,
+    randomness: &ChallengeSeed,
+    prover_id: ProverId,
+    mut pub_sectors: Vec<PublicSector<DefaultTreeDomain>>,
+    vanilla_proofs: VanillaWinningPoStProofs,
+) -> Result<SnarkProof> {
+    let post_config = proof_type.as_v1_config();
+
+    let pub_sectors: Vec<PublicSector<<<Tree as MerkleTreeTrait>::Hasher as Hasher>::Domain>> = unsafe {
+        Vec::from_raw_parts(
+            pub_sectors.as_mut_ptr().cast(),
+            pub_sectors.len(),
+            pub_sectors.capacity(),
+        )
+    };
+    let raw_vanilla_proofs = vanilla_proofs.try_into_raw::<Tree>()?;
+    proof::generate_winning_post_snark_proof::<Tree>(
+        &post_config,
+        randomness,
+        prover_id,
+        pub_sectors,
+        raw_vanilla_proofs,
+    )
+}
+
+/// Verifies a Window PoSt `SnarkProof` produced by [`generate_window_post_snark_proof`].
+///
+/// # Arguments
+/// * `randomness` - Random seed value used for the PoSt challenge.
+/// * `prover_id` - Unique ID of the storage provider.
+/// * `pub_sectors` - Public sector commitments the proof covers.
+/// * `proof` - The groth16 proof bytes to check.
+pub fn verify_window_post(
+    proof_type: RegisteredPoStProof,
+    randomness: &ChallengeSeed,
+    prover_id: ProverId,
+    pub_sectors: Vec<PublicSector<DefaultTreeDomain>>,
+    proof: &SnarkProof,
+) -> Result<bool> {
+    with_shape!(
+        u64::from(proof_type.sector_size()),
+        verify_window_post_inner,
+        proof_type,
+        randomness,
+        prover_id,
+        pub_sectors,
+        proof,
+    )
+}
+
+fn verify_window_post_inner<Tree: 'static + MerkleTreeTrait>(
+    proof_type: RegisteredPoStProof,
+    randomness: &ChallengeSeed,
+    prover_id: ProverId,
+    mut pub_sectors: Vec<PublicSector<DefaultTreeDomain>>,
+    proof: &SnarkProof,
+) -> Result<bool> {
+    let post_config = proof_type.as_v1_config();
+
+    let pub_sectors: Vec<PublicSector<<<Tree as MerkleTreeTrait>::Hasher as Hasher>::Domain>> = unsafe {
+        Vec::from_raw_parts(
+            pub_sectors.as_mut_ptr().cast(),
+            pub_sectors.len(),
+            pub_sectors.capacity(),
+        )
+    };
+    proof::verify_window_post::<Tree>(&post_config, randomness, prover_id, pub_sectors, proof)
+}
+
+/// One (miner, proof, public sectors) tuple to check in [`batch_verify_window_post`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WindowPoStVerifyInput {
+    pub proof_type: RegisteredPoStProof,
+    pub randomness: ChallengeSeed,
+    pub prover_id: ProverId,
+    pub pub_sectors: Vec<PublicSector<DefaultTreeDomain>>,
+    pub proof: SnarkProof,
+}
+
+/// Verifies many miners' Window PoSt proofs at once.
+///
+/// Rather than running an independent Groth16 verification per proof, every proof's
+/// prepared public inputs are accumulated into a single random-linear-combination check,
+/// so `M` proofs cost roughly one multi-Miller-loop pairing check plus `M` scalar-mul
+/// accumulations. All entries must share the same `proof_type`, since the batch is
+/// checked against a single verifying key.
+pub fn batch_verify_window_post(inputs: Vec<WindowPoStVerifyInput>) -> Result<bool> {
+    ensure!(!inputs.is_empty(), "no proofs supplied");
+    let proof_type = inputs[0].proof_type;
+    ensure!(
+        inputs.iter().all(|i| i.proof_type == proof_type),
+        "batch_verify_window_post requires a uniform proof_type across the batch"
+    );
+
+    with_shape!(
+        u64::from(proof_type.sector_size()),
+        batch_verify_window_post_inner,
+        proof_type,
+        inputs,
+    )
+}
+
+fn batch_verify_window_post_inner<Tree: 'static + MerkleTreeTrait>(
+    proof_type: RegisteredPoStProof,
+    inputs: Vec<WindowPoStVerifyInput>,
+) -> Result<bool> {
+    let post_config = proof_type.as_v1_config();
+
+    let entries = inputs
+        .into_iter()
+        .map(|input| {
+            let mut pub_sectors = input.pub_sectors;
+            let pub_sectors: Vec<PublicSector<<<Tree as MerkleTreeTrait>::Hasher as Hasher>::Domain>> = unsafe {
+                Vec::from_raw_parts(
+                    pub_sectors.as_mut_ptr().cast(),
+                    pub_sectors.len(),
+                    pub_sectors.capacity(),
+                )
+            };
+            (input.randomness, input.prover_id, pub_sectors, input.proof)
+        })
+        .collect::<Vec<_>>();
+
+    proof::batch_verify_window_post::<Tree>(&post_config, &entries)
+}
@@ -3,9 +3,13 @@ use std::collections::BTreeMap;
 use anyhow::ensure;
 use anyhow::Context;
 use anyhow::Result;
+use bellperson::groth16::verify_proofs_batch;
+use bellperson::groth16::Proof as Groth16Proof;
+use blstrs::Bls12;
 use filecoin_hashers::Hasher;
 use filecoin_proofs::as_safe_commitment;
 use filecoin_proofs::parameters::window_post_setup_params;
+use filecoin_proofs::parameters::winning_post_setup_params;
 use filecoin_proofs::types::ChallengeSeed;
 use filecoin_proofs::types::PoStConfig;
 use filecoin_proofs::types::PrivateReplicaInfo;
@@ -13,20 +17,25 @@ use filecoin_proofs::types::ProverId;
 use filecoin_proofs::types::SnarkProof;
 use filecoin_proofs::PoStType;
 use filecoin_proofs::VanillaProof as RawVanillaPoStProof;
+use rand::rngs::OsRng;
 use rayon::prelude::IntoParallelRefIterator;
 use rayon::prelude::ParallelIterator;
 use storage_proofs_core::compound_proof::CompoundProof;
+use storage_proofs_core::compound_proof::MultiProof;
 use storage_proofs_core::compound_proof::{self};
 use storage_proofs_core::merkle::MerkleTreeTrait;
 use storage_proofs_core::proof::ProofScheme;
 use storage_proofs_core::sector::SectorId;
+use storage_proofs_post::fallback::ChallengeRequirements;
 use storage_proofs_post::fallback::FallbackPoSt;
+use storage_proofs_post::fallback::FallbackPoStCircuit;
 use storage_proofs_post::fallback::FallbackPoStCompound;
 use storage_proofs_post::fallback::PrivateSector;
 use storage_proofs_post::fallback::PublicSector;
 use storage_proofs_post::fallback::{self};
 
 use crate::caches::get_post_params;
+use crate::caches::get_post_verifying_key;
 use crate::util;
 use crate::util::get_partitions_for_window_post;
 
@@ -164,3 +173,247 @@ pub(crate) fn generate_window_post_snark_proof<Tree: 'static + MerkleTreeTrait>(
     )?;
     util::proofs_to_bytes(&groth_proofs)
 }
+
+/// Unlike Window PoSt, Winning PoSt challenges a single sector/partition selected at
+/// block-production time, so it is never split across multiple partitions.
+pub(crate) fn generate_winning_post_vanilla_proofs<Tree: 'static + MerkleTreeTrait>(
+    post_config: &PoStConfig,
+    randomness: &ChallengeSeed,
+    replicas: &BTreeMap<SectorId, PrivateReplicaInfo<Tree>>,
+    prover_id: ProverId,
+) -> Result<(
+    Vec<PublicSector<TreeDomain<Tree>>>,
+    Vec<RawVanillaPoStProof<Tree>>,
+)> {
+    ensure!(
+        post_config.typ == PoStType::Winning,
+        "invalid post config type"
+    );
+
+    let randomness_safe = as_safe_commitment(randomness, "randomness")?;
+    let prover_id_safe = as_safe_commitment(&prover_id, "prover_id")?;
+
+    let vanilla_params = winning_post_setup_params(post_config)?;
+    let sector_count = vanilla_params.sector_count;
+    let setup_params = compound_proof::SetupParams {
+        vanilla_params,
+        partitions: None,
+        priority: post_config.priority,
+    };
+
+    let pub_params: compound_proof::PublicParams<'_, FallbackPoSt<'_, Tree>> =
+        FallbackPoStCompound::setup(&setup_params)?;
+
+    let trees: Vec<_> = replicas
+        .par_iter()
+        .map(|(sector_id, replica)| {
+            replica
+                .merkle_tree(post_config.sector_size)
+                .with_context(|| {
+                    format!("generate_winning_post: merkle_tree failed: {:?}", sector_id)
+                })
+        })
+        .collect::<Result<_>>()?;
+
+    let mut pub_sectors = Vec::with_capacity(sector_count);
+    let mut priv_sectors = Vec::with_capacity(sector_count);
+
+    for ((sector_id, replica), tree) in replicas.iter().zip(trees.iter()) {
+        let comm_r = replica.safe_comm_r().with_context(|| {
+            format!("generate_winning_post: safe_comm_r failed: {:?}", sector_id)
+        })?;
+        let comm_c = replica.safe_comm_c();
+        let comm_r_last = replica.safe_comm_r_last();
+
+        pub_sectors.push(PublicSector {
+            id: *sector_id,
+            comm_r,
+        });
+        priv_sectors.push(PrivateSector {
+            tree,
+            comm_c,
+            comm_r_last,
+        });
+    }
+
+    let pub_inputs = fallback::PublicInputs {
+        randomness: randomness_safe,
+        prover_id: prover_id_safe,
+        sectors: pub_sectors,
+        k: None,
+    };
+
+    let priv_inputs = fallback::PrivateInputs::<Tree> {
+        sectors: &priv_sectors,
+    };
+
+    let raw_vanilla_proof = <FallbackPoSt<'_, Tree> as ProofScheme<'_>>::prove_all_partitions(
+        &pub_params.vanilla_params,
+        &pub_inputs,
+        &priv_inputs,
+        1,
+    )?;
+
+    Ok((pub_inputs.sectors, raw_vanilla_proof))
+}
+
+pub(crate) fn generate_winning_post_snark_proof<Tree: 'static + MerkleTreeTrait>(
+    post_config: &PoStConfig,
+    randomness: &ChallengeSeed,
+    prover_id: ProverId,
+    sectors: Vec<PublicSector<<<Tree as MerkleTreeTrait>::Hasher as Hasher>::Domain>>,
+    vanilla_proofs: Vec<RawVanillaPoStProof<Tree>>,
+) -> Result<SnarkProof> {
+    let randomness_safe = as_safe_commitment(randomness, "randomness")?;
+    let prover_id_safe = as_safe_commitment(&prover_id, "prover_id")?;
+
+    let vanilla_params = winning_post_setup_params(post_config)?;
+    let setup_params = compound_proof::SetupParams {
+        vanilla_params,
+        partitions: None,
+        priority: post_config.priority,
+    };
+
+    let pub_params: compound_proof::PublicParams<'_, FallbackPoSt<'_, Tree>> =
+        FallbackPoStCompound::setup(&setup_params)?;
+
+    let groth_params = get_post_params::<Tree>(post_config)?;
+
+    let pub_inputs = fallback::PublicInputs {
+        randomness: randomness_safe,
+        prover_id: prover_id_safe,
+        sectors,
+        k: None,
+    };
+
+    let groth_proofs = FallbackPoStCompound::<Tree>::circuit_proofs(
+        &pub_inputs,
+        vanilla_proofs,
+        &pub_params.vanilla_params,
+        &groth_params,
+        pub_params.priority,
+    )?;
+    util::proofs_to_bytes(&groth_proofs)
+}
+
+pub(crate) fn verify_window_post<Tree: 'static + MerkleTreeTrait>(
+    post_config: &PoStConfig,
+    randomness: &ChallengeSeed,
+    prover_id: ProverId,
+    sectors: Vec<PublicSector<TreeDomain<Tree>>>,
+    proof: &SnarkProof,
+) -> Result<bool> {
+    ensure!(
+        post_config.typ == PoStType::Window,
+        "invalid post config type"
+    );
+
+    let randomness_safe = as_safe_commitment(randomness, "randomness")?;
+    let prover_id_safe = as_safe_commitment(&prover_id, "prover_id")?;
+
+    let vanilla_params = window_post_setup_params(post_config);
+    let partitions = get_partitions_for_window_post(sectors.len(), post_config);
+
+    let setup_params = compound_proof::SetupParams {
+        vanilla_params,
+        partitions,
+        priority: post_config.priority,
+    };
+
+    let pub_params: compound_proof::PublicParams<'_, FallbackPoSt<'_, Tree>> =
+        FallbackPoStCompound::setup(&setup_params)?;
+
+    let pub_inputs = fallback::PublicInputs {
+        randomness: randomness_safe,
+        prover_id: prover_id_safe,
+        sectors,
+        k: None,
+    };
+
+    let verifying_key = get_post_verifying_key::<Tree>(post_config)?;
+    let multi_proof = MultiProof::new_from_reader(partitions, proof.as_slice(), &verifying_key)
+        .context("parse window post groth proof")?;
+
+    let is_valid = FallbackPoStCompound::verify(
+        &pub_params,
+        &pub_inputs,
+        &multi_proof,
+        &ChallengeRequirements {
+            minimum_challenge_count: post_config.challenge_count * post_config.sector_count,
+        },
+    )?;
+
+    Ok(is_valid)
+}
+
+/// Verifies many (randomness, prover_id, sectors, proof) tuples together.
+///
+/// Instead of running `entries.len()` independent Groth16 verifications, every proof's
+/// prepared public inputs are collected and checked with a single random-linear-combination
+/// multi-Miller-loop, so the whole batch costs roughly one pairing check plus a scalar-mul
+/// accumulation per proof. The whole batch is rejected if the aggregate check fails, even if
+/// only one proof in it is invalid.
+pub(crate) fn batch_verify_window_post<Tree: 'static + MerkleTreeTrait>(
+    post_config: &PoStConfig,
+    entries: &[(ChallengeSeed, ProverId, Vec<PublicSector<TreeDomain<Tree>>>, SnarkProof)],
+) -> Result<bool> {
+    ensure!(!entries.is_empty(), "no proofs supplied");
+    ensure!(
+        post_config.typ == PoStType::Window,
+        "invalid post config type"
+    );
+
+    let verifying_key = get_post_verifying_key::<Tree>(post_config)?;
+
+    let mut groth_proofs = Vec::with_capacity(entries.len());
+    let mut prepared_inputs = Vec::with_capacity(entries.len());
+
+    for (randomness, prover_id, sectors, proof) in entries {
+        let randomness_safe = as_safe_commitment(randomness, "randomness")?;
+        let prover_id_safe = as_safe_commitment(prover_id, "prover_id")?;
+
+        let vanilla_params = window_post_setup_params(post_config);
+        let partitions = get_partitions_for_window_post(sectors.len(), post_config);
+        let setup_params = compound_proof::SetupParams {
+            vanilla_params,
+            partitions,
+            priority: post_config.priority,
+        };
+        let pub_params: compound_proof::PublicParams<'_, FallbackPoSt<'_, Tree>> =
+            FallbackPoStCompound::setup(&setup_params)?;
+
+        let pub_inputs = fallback::PublicInputs {
+            randomness: randomness_safe,
+            prover_id: prover_id_safe,
+            sectors: sectors.clone(),
+            k: None,
+        };
+
+        ensure!(
+            proof.len() % Groth16Proof::<Bls12>::size() == 0,
+            "malformed window post proof bytes"
+        );
+        let partition_count = pub_params.partitions.unwrap_or(1);
+        ensure!(
+            partition_count == proof.len() / Groth16Proof::<Bls12>::size(),
+            "proof partition count mismatch"
+        );
+
+        for (k, chunk) in proof.chunks(Groth16Proof::<Bls12>::size()).enumerate() {
+            groth_proofs.push(Groth16Proof::<Bls12>::read(chunk).context("read groth proof")?);
+
+            let inputs = <FallbackPoStCompound<Tree> as CompoundProof<
+                FallbackPoSt<'_, Tree>,
+                FallbackPoStCircuit<Tree>,
+            >>::generate_public_inputs(&pub_inputs, &pub_params.vanilla_params, Some(k))?;
+            prepared_inputs.push(inputs);
+        }
+    }
+
+    let proof_refs: Vec<&Groth16Proof<Bls12>> = groth_proofs.iter().collect();
+
+    let is_valid = verify_proofs_batch(&verifying_key, &mut OsRng, &proof_refs, &prepared_inputs)
+        .context("batch verify window post proofs")?;
+
+    Ok(is_valid)
+}